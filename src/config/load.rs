@@ -1,6 +1,13 @@
-use crate::config::RasGBConfig;
+use crate::config::{CorsConfig, RasGBConfig};
+use crate::display::brightness::MAX_BRIGHTNESS;
+use crate::web::cors::{
+    CorsList, WebServerCorsConfig, DEFAULT_ALLOWED_HEADERS, DEFAULT_ALLOWED_METHODS,
+    DEFAULT_EXPOSED_HEADERS,
+};
+use std::collections::HashSet;
 use std::fs;
 use std::path::Path;
+use std::time::Duration;
 use thiserror::Error;
 
 pub fn read_config_from_env() -> Result<RasGBConfig, ConfigLoadError> {
@@ -46,10 +53,106 @@ fn read_config(file_path: impl AsRef<Path>) -> Result<RasGBConfig, ConfigLoadErr
 
     // Parse the config content from TOML into the Config struct
     let config = toml::from_str(&config_content)?;
+    validate_config(&config)?;
 
     Ok(config)
 }
 
+fn validate_config(config: &RasGBConfig) -> Result<(), ConfigLoadError> {
+    if config
+        .display
+        .brightness
+        .is_some_and(|brightness| brightness > MAX_BRIGHTNESS)
+    {
+        return Err(ConfigLoadError::InvalidConfig {
+            details: format!("`display.brightness` must be at most {}", MAX_BRIGHTNESS),
+        });
+    }
+
+    let mut priorities = HashSet::new();
+    for (name, channel) in config.channels.iter() {
+        if name.parse::<i8>().is_ok() {
+            return Err(ConfigLoadError::InvalidConfig {
+                details: format!("channel name `{}` would shadow a channel index", name),
+            });
+        }
+        if channel
+            .opacity
+            .is_some_and(|opacity| !(0.0..=1.0).contains(&opacity))
+        {
+            return Err(ConfigLoadError::InvalidConfig {
+                details: format!("channel `{}` opacity must be between 0 and 1", name),
+            });
+        }
+        if !priorities.insert(channel.priority) {
+            return Err(ConfigLoadError::InvalidConfig {
+                details: format!(
+                    "channel `{}` shares priority {} with another channel",
+                    name, channel.priority
+                ),
+            });
+        }
+    }
+    if config.server.tls.is_some() && !cfg!(feature = "tls") {
+        return Err(ConfigLoadError::InvalidConfig {
+            details: "feature `tls` is not enabled but required for `server.tls`".to_string(),
+        });
+    }
+    if let Some(cors) = &config.server.cors {
+        parse_cors_config(cors)?;
+    }
+    if let Some(rate_limit) = &config.rate_limit {
+        let rules = [
+            ("channel", &rate_limit.channel),
+            ("client", &rate_limit.client),
+        ];
+        for (name, rule) in rules {
+            let Some(rule) = rule else {
+                continue;
+            };
+            let rates = [rule.frames_per_second, rule.bytes_per_second];
+            if rates
+                .into_iter()
+                .flatten()
+                .any(|rate| !(rate > 0.0 && rate.is_finite()))
+            {
+                return Err(ConfigLoadError::InvalidConfig {
+                    details: format!("`rate_limit.{}` rates must be positive numbers", name),
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn parse_cors_config(cors: &CorsConfig) -> Result<WebServerCorsConfig, ConfigLoadError> {
+    fn parse_list<T: std::str::FromStr>(
+        field: &str,
+        values: &[impl AsRef<str>],
+    ) -> Result<CorsList<T>, ConfigLoadError> {
+        CorsList::parse(values).map_err(|details| ConfigLoadError::InvalidConfig {
+            details: format!("`server.cors.{}`: {}", field, details),
+        })
+    }
+
+    Ok(WebServerCorsConfig {
+        allowed_origins: parse_list("allowed_origins", &cors.allowed_origins)?,
+        allowed_methods: match &cors.allowed_methods {
+            Some(methods) => parse_list("allowed_methods", methods)?,
+            None => parse_list("allowed_methods", &DEFAULT_ALLOWED_METHODS)?,
+        },
+        allowed_headers: match &cors.allowed_headers {
+            Some(headers) => parse_list("allowed_headers", headers)?,
+            None => parse_list("allowed_headers", &DEFAULT_ALLOWED_HEADERS)?,
+        },
+        exposed_headers: match &cors.exposed_headers {
+            Some(headers) => parse_list("exposed_headers", headers)?,
+            None => parse_list("exposed_headers", &DEFAULT_EXPOSED_HEADERS)?,
+        },
+        max_age: cors.max_age_seconds.map(Duration::from_secs),
+    })
+}
+
 #[derive(Error, Debug)]
 pub enum ConfigLoadError {
     #[error("file was not found at: {path:?}")]
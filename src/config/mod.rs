@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::net::IpAddr;
+use std::path::PathBuf;
 
 mod load;
 
@@ -10,11 +12,18 @@ pub struct RasGBConfig {
     pub display: DisplayConfig,
     pub server: ServerConfig,
     pub timing: TimingConfig,
+    pub auth: Option<AuthConfig>,
+    #[serde(default)]
+    pub channels: BTreeMap<String, ChannelConfig>,
+    pub rate_limit: Option<RateLimitConfig>,
+    #[serde(default)]
+    pub compositing: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct DisplayConfig {
     pub fps: f64,
+    pub brightness: Option<u8>,
     pub driver: DisplayConfigDriver,
 }
 
@@ -30,6 +39,24 @@ pub struct ServerConfig {
     pub ip: IpAddr,
     #[serde(default = "default_port")]
     pub port: u16,
+    pub tls: Option<ServerTlsConfig>,
+    pub time_sync_port: Option<u16>,
+    pub cors: Option<CorsConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ServerTlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CorsConfig {
+    pub allowed_origins: Vec<String>,
+    pub allowed_methods: Option<Vec<String>>,
+    pub allowed_headers: Option<Vec<String>>,
+    pub exposed_headers: Option<Vec<String>>,
+    pub max_age_seconds: Option<u64>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -37,6 +64,47 @@ pub struct TimingConfig {
     pub idle_seconds: Option<f64>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelConfig {
+    pub priority: i8,
+    pub idle_seconds: Option<f64>,
+    pub allowed_dimensions: Option<Vec<ChannelDimensionsConfig>>,
+    pub description: Option<String>,
+    pub z_order: Option<i32>,
+    pub opacity: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ChannelDimensionsConfig {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RateLimitConfig {
+    pub channel: Option<RateLimitRuleConfig>,
+    pub client: Option<RateLimitRuleConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RateLimitRuleConfig {
+    pub frames_per_second: Option<f64>,
+    pub bytes_per_second: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthConfig {
+    pub tokens: Vec<AuthTokenConfig>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AuthTokenConfig {
+    pub token: String,
+    pub channels: Option<Vec<i8>>,
+    #[serde(default)]
+    pub read_only: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum DisplayConfigDriver {
     #[serde(rename = "winit_pixels")]
@@ -69,3 +137,14 @@ pub enum DisplayConfigDriver {
         show_refresh_rate: Option<bool>,
     },
 }
+
+impl DisplayConfigDriver {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DisplayConfigDriver::WinitPixels { .. } => "winit_pixels",
+            DisplayConfigDriver::Fake { .. } => "fake",
+            DisplayConfigDriver::Tui { .. } => "tui",
+            DisplayConfigDriver::RgbLedMatrix { .. } => "rgb_led_matrix",
+        }
+    }
+}
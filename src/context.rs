@@ -1,7 +1,9 @@
 use crate::config::RasGBConfig;
 use crate::display::Display;
+use crate::event::EventBus;
 use crate::frame::filler::letterboxing::LetterboxingDisplayFiller;
 use crate::frame::gen::FrameGenerator;
+use crate::metrics::Metrics;
 use tokio_util::sync::CancellationToken;
 
 pub struct RasGBContext {
@@ -10,6 +12,8 @@ pub struct RasGBContext {
     pub display: Box<dyn Display + 'static>,
     pub generator: Box<dyn FrameGenerator>,
     pub filler: LetterboxingDisplayFiller,
+    pub events: EventBus,
+    pub metrics: Metrics,
 
     pub shutdown_token: CancellationToken,
 }
@@ -0,0 +1,125 @@
+use crate::display::{Dimensions, Display, DisplayError, Pixel};
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+pub const MAX_BRIGHTNESS: u8 = 100;
+
+#[derive(Clone)]
+pub struct DisplayBrightness {
+    level: Arc<AtomicU8>,
+    is_native: Arc<AtomicBool>,
+}
+
+impl DisplayBrightness {
+    fn new(level: u8) -> Self {
+        Self {
+            level: Arc::new(AtomicU8::new(level)),
+            is_native: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    pub fn level(&self) -> u8 {
+        self.level.load(Ordering::Relaxed)
+    }
+
+    pub fn set_level(&self, level: u8) {
+        self.level
+            .store(u8::min(level, MAX_BRIGHTNESS), Ordering::Relaxed);
+    }
+
+    pub fn is_native(&self) -> bool {
+        self.is_native.load(Ordering::Relaxed)
+    }
+}
+
+/// Applies the brightness natively when the display supports it and scales pixels otherwise.
+pub struct BrightnessDisplay<D: Display> {
+    display: D,
+    brightness: DisplayBrightness,
+    applied_level: RefCell<Option<u8>>,
+    last_pixels: RefCell<Option<Vec<Pixel>>>,
+}
+
+impl<D: Display> BrightnessDisplay<D> {
+    pub fn new(display: D, level: u8) -> Self {
+        Self {
+            display,
+            brightness: DisplayBrightness::new(u8::min(level, MAX_BRIGHTNESS)),
+            applied_level: RefCell::new(None),
+            last_pixels: RefCell::new(None),
+        }
+    }
+
+    pub fn brightness(&self) -> DisplayBrightness {
+        self.brightness.clone()
+    }
+
+    fn apply_level(&self) -> u8 {
+        let level = self.brightness.level();
+        let mut applied_level = self.applied_level.borrow_mut();
+        if *applied_level != Some(level) {
+            let is_native = self.display.set_brightness(level);
+            self.brightness
+                .is_native
+                .store(is_native, Ordering::Relaxed);
+            *applied_level = Some(level);
+        }
+        level
+    }
+
+    fn show(&self, pixels: &[Pixel]) -> Result<(), DisplayError> {
+        let level = self.apply_level();
+        let base_level = self.display.base_brightness();
+        if self.brightness.is_native() || level == base_level {
+            return self.display.update_pixels(pixels.to_vec());
+        }
+        let scale_channel = |value: u8| scale_channel(value, level, base_level);
+        let scaled_pixels = pixels
+            .iter()
+            .map(|pixel| Pixel {
+                r: scale_channel(pixel.r),
+                g: scale_channel(pixel.g),
+                b: scale_channel(pixel.b),
+            })
+            .collect();
+        self.display.update_pixels(scaled_pixels)
+    }
+}
+
+// Levels above the base level can't be shown brighter than full intensity
+fn scale_channel(value: u8, level: u8, base_level: u8) -> u8 {
+    let scaled = value as u16 * level as u16 / u16::max(base_level as u16, 1);
+    u16::min(scaled, u8::MAX as u16) as u8
+}
+
+impl<D: Display> Display for BrightnessDisplay<D> {
+    fn dimensions(&self) -> Dimensions {
+        self.display.dimensions()
+    }
+
+    fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
+        self.show(&pixels)?;
+        *self.last_pixels.borrow_mut() = Some(pixels);
+        Ok(())
+    }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        self.brightness.set_level(level);
+        true
+    }
+
+    fn refresh(&self) -> Result<(), DisplayError> {
+        // Re-render the last frame so brightness changes show without waiting for a new one
+        let level_changed = *self.applied_level.borrow() != Some(self.brightness.level());
+        if level_changed {
+            if let Some(pixels) = self.last_pixels.borrow().as_ref() {
+                self.show(pixels)?;
+            }
+        }
+        self.display.refresh()
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+struct RecordingDisplay {
+    pixels: Rc<RefCell<Vec<Pixel>>>,
+    native_level: Option<Rc<RefCell<u8>>>,
+    base_level: Option<u8>,
+}
+
+impl Display for RecordingDisplay {
+    fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            width: 1,
+            height: 1,
+        }
+    }
+
+    fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
+        self.pixels.replace(pixels);
+        Ok(())
+    }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        match &self.native_level {
+            Some(native_level) => {
+                native_level.replace(level);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn base_brightness(&self) -> u8 {
+        self.base_level.unwrap_or(MAX_BRIGHTNESS)
+    }
+}
+
+fn white() -> Pixel {
+    Pixel {
+        r: 255,
+        g: 255,
+        b: 255,
+    }
+}
+
+#[test]
+fn test_software_brightness_scales_pixels_and_refreshes() {
+    let recording = RecordingDisplay::default();
+    let display = BrightnessDisplay::new(recording.clone(), 50);
+
+    display.update_pixels(vec![white()]).unwrap();
+    assert!(
+        recording.pixels.borrow()[0]
+            == Pixel {
+                r: 127,
+                g: 127,
+                b: 127
+            }
+    );
+    assert!(!display.brightness().is_native());
+
+    display.brightness().set_level(0);
+    display.refresh().unwrap();
+    assert!(recording.pixels.borrow()[0] == Pixel { r: 0, g: 0, b: 0 });
+}
+
+#[test]
+fn test_native_brightness_leaves_pixels_untouched() {
+    let native_level = Rc::new(RefCell::new(MAX_BRIGHTNESS));
+    let recording = RecordingDisplay {
+        native_level: Some(Rc::clone(&native_level)),
+        ..Default::default()
+    };
+    let display = BrightnessDisplay::new(recording.clone(), 30);
+
+    display.update_pixels(vec![white()]).unwrap();
+    assert!(recording.pixels.borrow()[0] == white());
+    assert_eq!(*native_level.borrow(), 30);
+    assert!(display.brightness().is_native());
+}
+
+#[test]
+fn test_software_brightness_scales_relative_to_base_level() {
+    let recording = RecordingDisplay {
+        base_level: Some(50),
+        ..Default::default()
+    };
+    let display = BrightnessDisplay::new(recording.clone(), 50);
+
+    display.update_pixels(vec![white()]).unwrap();
+    assert!(recording.pixels.borrow()[0] == white());
+
+    display.brightness().set_level(25);
+    display.refresh().unwrap();
+    assert!(
+        recording.pixels.borrow()[0]
+            == Pixel {
+                r: 127,
+                g: 127,
+                b: 127
+            }
+    );
+
+    display.brightness().set_level(100);
+    display.refresh().unwrap();
+    assert!(recording.pixels.borrow()[0] == white());
+}
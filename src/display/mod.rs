@@ -1,11 +1,15 @@
+pub mod brightness;
 pub mod fake;
+pub mod output;
 #[cfg(feature = "winit")]
 pub mod pixels;
 #[cfg(feature = "rpi")]
 pub mod rgb_led_matrix;
+pub mod snapshot;
 #[cfg(feature = "tui")]
 pub(crate) mod tui;
 
+use crate::display::brightness::MAX_BRIGHTNESS;
 use crate::frame::gen::FrameGenerator;
 use std::sync::Arc;
 use thiserror::Error;
@@ -23,10 +27,31 @@ pub struct Dimensions {
     pub height: u32,
 }
 
+#[derive(Clone, PartialEq, Eq)]
+pub struct Position {
+    pub x: u32,
+    pub y: u32,
+}
+
 pub trait Display {
     fn dimensions(&self) -> Dimensions;
 
     fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError>;
+
+    /// Returns whether the display applies the brightness itself, pixels are scaled otherwise.
+    fn set_brightness(&self, _level: u8) -> bool {
+        false
+    }
+
+    /// Brightness the display always applies by itself, pixels are scaled relative to it.
+    fn base_brightness(&self) -> u8 {
+        MAX_BRIGHTNESS
+    }
+
+    /// Called on every tick, even when no new frame is pushed.
+    fn refresh(&self) -> Result<(), DisplayError> {
+        Ok(())
+    }
 }
 
 impl<T: Display + ?Sized> Display for Box<T> {
@@ -37,6 +62,18 @@ impl<T: Display + ?Sized> Display for Box<T> {
     fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
         (**self).update_pixels(pixels)
     }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        (**self).set_brightness(level)
+    }
+
+    fn base_brightness(&self) -> u8 {
+        (**self).base_brightness()
+    }
+
+    fn refresh(&self) -> Result<(), DisplayError> {
+        (**self).refresh()
+    }
 }
 
 impl<T: Display + ?Sized> Display for Arc<T> {
@@ -47,6 +84,18 @@ impl<T: Display + ?Sized> Display for Arc<T> {
     fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
         (**self).update_pixels(pixels)
     }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        (**self).set_brightness(level)
+    }
+
+    fn base_brightness(&self) -> u8 {
+        (**self).base_brightness()
+    }
+
+    fn refresh(&self) -> Result<(), DisplayError> {
+        (**self).refresh()
+    }
 }
 
 #[derive(Error, Debug)]
@@ -0,0 +1,103 @@
+use crate::display::{Dimensions, Display, DisplayError, Pixel};
+use std::cell::RefCell;
+use std::sync::{Arc, Mutex};
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum OutputState {
+    On,
+    Blank,
+    Frozen,
+}
+
+#[derive(Clone)]
+pub struct OutputControl {
+    state: Arc<Mutex<OutputState>>,
+}
+
+impl OutputControl {
+    pub fn state(&self) -> OutputState {
+        *self.state.lock().unwrap()
+    }
+
+    pub fn set_state(&self, state: OutputState) {
+        *self.state.lock().unwrap() = state;
+    }
+}
+
+/// Holds back or blanks the output while frames keep being generated and pushed.
+pub struct OutputDisplay<D: Display> {
+    display: D,
+    control: OutputControl,
+    applied_state: RefCell<OutputState>,
+    latest_pixels: RefCell<Option<Vec<Pixel>>>,
+}
+
+impl<D: Display> OutputDisplay<D> {
+    pub fn new(display: D) -> Self {
+        Self {
+            display,
+            control: OutputControl {
+                state: Arc::new(Mutex::new(OutputState::On)),
+            },
+            applied_state: RefCell::new(OutputState::On),
+            latest_pixels: RefCell::new(None),
+        }
+    }
+
+    pub fn control(&self) -> OutputControl {
+        self.control.clone()
+    }
+
+    fn apply_state(&self, state: OutputState) -> Result<(), DisplayError> {
+        match state {
+            OutputState::On => {
+                if let Some(pixels) = self.latest_pixels.borrow().as_ref() {
+                    self.display.update_pixels(pixels.clone())?;
+                }
+            }
+            OutputState::Blank => {
+                let dimensions = self.display.dimensions();
+                let black = Pixel { r: 0, g: 0, b: 0 };
+                let pixel_count = (dimensions.width * dimensions.height) as usize;
+                self.display.update_pixels(vec![black; pixel_count])?;
+            }
+            OutputState::Frozen => {}
+        }
+        *self.applied_state.borrow_mut() = state;
+        Ok(())
+    }
+}
+
+impl<D: Display> Display for OutputDisplay<D> {
+    fn dimensions(&self) -> Dimensions {
+        self.display.dimensions()
+    }
+
+    fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
+        let state = self.control.state();
+        if state == OutputState::On {
+            self.display.update_pixels(pixels.clone())?;
+            *self.applied_state.borrow_mut() = state;
+        } else if *self.applied_state.borrow() != state {
+            self.apply_state(state)?;
+        }
+        // Kept while held back, so resuming shows the current content instead of stale frames
+        *self.latest_pixels.borrow_mut() = Some(pixels);
+        Ok(())
+    }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        self.display.set_brightness(level)
+    }
+
+    fn refresh(&self) -> Result<(), DisplayError> {
+        let state = self.control.state();
+        if *self.applied_state.borrow() != state {
+            self.apply_state(state)?;
+        }
+        self.display.refresh()
+    }
+}
@@ -0,0 +1,61 @@
+use super::*;
+use std::rc::Rc;
+
+#[derive(Clone, Default)]
+struct RecordingDisplay {
+    updates: Rc<RefCell<Vec<Vec<Pixel>>>>,
+}
+
+impl Display for RecordingDisplay {
+    fn dimensions(&self) -> Dimensions {
+        Dimensions {
+            width: 1,
+            height: 1,
+        }
+    }
+
+    fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
+        self.updates.borrow_mut().push(pixels);
+        Ok(())
+    }
+}
+
+fn gray(value: u8) -> Pixel {
+    Pixel {
+        r: value,
+        g: value,
+        b: value,
+    }
+}
+
+#[test]
+fn test_frozen_output_resumes_with_latest_pixels() {
+    let recording = RecordingDisplay::default();
+    let display = OutputDisplay::new(recording.clone());
+    display.update_pixels(vec![gray(1)]).unwrap();
+
+    display.control().set_state(OutputState::Frozen);
+    display.refresh().unwrap();
+    display.update_pixels(vec![gray(2)]).unwrap();
+    display.update_pixels(vec![gray(3)]).unwrap();
+    assert_eq!(recording.updates.borrow().len(), 1);
+
+    display.control().set_state(OutputState::On);
+    display.refresh().unwrap();
+    assert!(recording.updates.borrow().last() == Some(&vec![gray(3)]));
+    assert_eq!(recording.updates.borrow().len(), 2);
+}
+
+#[test]
+fn test_blank_output_shows_black_once() {
+    let recording = RecordingDisplay::default();
+    let display = OutputDisplay::new(recording.clone());
+    display.update_pixels(vec![gray(1)]).unwrap();
+
+    display.control().set_state(OutputState::Blank);
+    display.update_pixels(vec![gray(2)]).unwrap();
+    display.refresh().unwrap();
+    display.update_pixels(vec![gray(3)]).unwrap();
+
+    assert!(*recording.updates.borrow() == vec![vec![gray(1)], vec![gray(0)]]);
+}
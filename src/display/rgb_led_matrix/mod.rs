@@ -1,3 +1,4 @@
+use crate::display::brightness::MAX_BRIGHTNESS;
 use crate::display::{Dimensions, Display, DisplayError, Pixel};
 use crate::lib::BlockingOption;
 use rpi_led_matrix::{LedColor, LedMatrix, LedMatrixOptions, LedRuntimeOptions};
@@ -10,6 +11,7 @@ pub struct RgbLedMatrixDisplay {
     dimensions: Dimensions,
     draw_thread_handle: Option<JoinHandle<()>>,
     data_sender: BlockingOption<Vec<Pixel>>,
+    brightness: u8,
     drop_token: CancellationToken,
 }
 
@@ -65,8 +67,15 @@ impl RgbLedMatrixDisplay {
             drop_token,
             draw_thread_handle: Some(draw_thread_handle),
             data_sender: pixels_sender,
+            brightness: MAX_BRIGHTNESS,
         }
     }
+
+    /// Tells the display which brightness the matrix was created with.
+    pub fn with_brightness(mut self, brightness: u8) -> Self {
+        self.brightness = brightness;
+        self
+    }
 }
 
 impl Display for RgbLedMatrixDisplay {
@@ -83,6 +92,15 @@ impl Display for RgbLedMatrixDisplay {
         self.data_sender.send(pixels);
         Ok(())
     }
+
+    // The matrix can't change its brightness after creation, other levels are scaled in software
+    fn set_brightness(&self, level: u8) -> bool {
+        level == self.brightness
+    }
+
+    fn base_brightness(&self) -> u8 {
+        self.brightness
+    }
 }
 
 impl Drop for RgbLedMatrixDisplay {
@@ -0,0 +1,51 @@
+use crate::display::{Dimensions, Display, DisplayError, Pixel};
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Default)]
+pub struct DisplaySnapshot {
+    pixels: Arc<Mutex<Option<Vec<Pixel>>>>,
+}
+
+impl DisplaySnapshot {
+    pub fn pixels(&self) -> Option<Vec<Pixel>> {
+        self.pixels.lock().unwrap().clone()
+    }
+}
+
+pub struct SnapshotDisplay<D: Display> {
+    display: D,
+    snapshot: DisplaySnapshot,
+}
+
+impl<D: Display> SnapshotDisplay<D> {
+    pub fn new(display: D) -> Self {
+        Self {
+            display,
+            snapshot: DisplaySnapshot::default(),
+        }
+    }
+
+    pub fn snapshot(&self) -> DisplaySnapshot {
+        self.snapshot.clone()
+    }
+}
+
+impl<D: Display> Display for SnapshotDisplay<D> {
+    fn dimensions(&self) -> Dimensions {
+        self.display.dimensions()
+    }
+
+    fn update_pixels(&self, pixels: Vec<Pixel>) -> Result<(), DisplayError> {
+        self.display.update_pixels(pixels.clone())?;
+        *self.snapshot.pixels.lock().unwrap() = Some(pixels);
+        Ok(())
+    }
+
+    fn set_brightness(&self, level: u8) -> bool {
+        self.display.set_brightness(level)
+    }
+
+    fn refresh(&self) -> Result<(), DisplayError> {
+        self.display.refresh()
+    }
+}
@@ -0,0 +1,54 @@
+use tokio::sync::broadcast;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone)]
+pub enum StatusEvent {
+    ActiveChannelChanged {
+        channel: Option<i8>,
+    },
+    FrameDropped {
+        channel: i8,
+        unix_micros: u128,
+        reason: FrameDropReason,
+    },
+    FallbackActivated,
+    DisplayFailed {
+        message: String,
+    },
+}
+
+#[derive(Clone, Copy)]
+pub enum FrameDropReason {
+    Evicted,
+    Superseded,
+    MissingPatchBase,
+}
+
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<StatusEvent>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+
+    pub fn emit(&self, event: StatusEvent) {
+        // Sending only fails if nobody is subscribed, in which case the event is irrelevant
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<StatusEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(256)
+    }
+}
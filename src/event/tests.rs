@@ -0,0 +1,78 @@
+use super::*;
+use crate::display::Pixel;
+use crate::frame::gen::channel_time_queued::ChannelTimeQueuedFrameGenerator;
+use crate::frame::gen::fallback::FallbackFrameGenerator;
+use crate::frame::gen::solid_color::SolidColorFrameGenerator;
+use crate::frame::gen::FrameGenerator;
+use crate::frame::Frame;
+use std::time::Duration;
+use tokio::sync::broadcast::error::TryRecvError;
+
+#[test]
+fn test_events_reach_every_subscriber() {
+    let events = EventBus::default();
+    events.emit(StatusEvent::FallbackActivated);
+
+    let mut first = events.subscribe();
+    let mut second = events.subscribe();
+    events.emit(StatusEvent::ActiveChannelChanged { channel: Some(2) });
+
+    for receiver in [&mut first, &mut second] {
+        assert!(matches!(
+            receiver.try_recv(),
+            Ok(StatusEvent::ActiveChannelChanged { channel: Some(2) })
+        ));
+        assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+    }
+}
+
+#[test]
+fn test_dropped_frames_emitted_with_reason() {
+    let events = EventBus::default();
+    let mut receiver = events.subscribe();
+    let gen = ChannelTimeQueuedFrameGenerator::new(2, 1.0).with_events(events);
+
+    gen.add_frame(0, 100, Frame::empty());
+    gen.add_frame(0, 100, Frame::empty());
+    gen.add_frame(0, 200, Frame::empty());
+    gen.add_frame(0, 300, Frame::empty());
+
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(StatusEvent::FrameDropped {
+            channel: 0,
+            unix_micros: 100,
+            reason: FrameDropReason::Superseded,
+        })
+    ));
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(StatusEvent::FrameDropped {
+            channel: 0,
+            unix_micros: 200,
+            reason: FrameDropReason::Evicted,
+        })
+    ));
+    assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+}
+
+#[test]
+fn test_fallback_activation_emitted_once() {
+    let events = EventBus::default();
+    let mut receiver = events.subscribe();
+    let gen = FallbackFrameGenerator::new(
+        ChannelTimeQueuedFrameGenerator::new(2, 1.0),
+        SolidColorFrameGenerator::new(Pixel { r: 0, g: 0, b: 0 }, 1, 1),
+        Duration::ZERO,
+    )
+    .with_events(events);
+
+    assert!(gen.generate(100).is_some());
+    assert!(gen.generate(200).is_some());
+
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(StatusEvent::FallbackActivated)
+    ));
+    assert!(matches!(receiver.try_recv(), Err(TryRecvError::Empty)));
+}
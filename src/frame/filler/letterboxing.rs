@@ -1,31 +1,55 @@
 use crate::display::{Display, DisplayError, Pixel};
 use crate::frame::filler::FrameFiller;
-use crate::frame::Frame;
+use crate::frame::{blend, Frame};
+use crate::metrics::Metrics;
 use anyhow::anyhow;
+use std::time::Instant;
 
 pub struct LetterboxingDisplayFiller {
     background_color: Pixel,
+    metrics: Metrics,
 }
 
 impl LetterboxingDisplayFiller {
     pub fn new(background_color: Pixel) -> Self {
-        Self { background_color }
+        Self {
+            background_color,
+            metrics: Metrics::default(),
+        }
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
     }
 }
 
 impl FrameFiller for LetterboxingDisplayFiller {
     fn push_to_display(&self, frame: Frame, display: &dyn Display) -> Result<(), DisplayError> {
+        let fill_started_at = Instant::now();
         let dimensions = display.dimensions();
         if frame.width > dimensions.width || frame.height > dimensions.height {
             return Err(DisplayError::FrameTooLarge);
         }
 
-        let padding_top = (dimensions.height - frame.height) / 2;
-        let padding_bottom = dimensions.height - frame.height - padding_top;
-        let padding_left = (dimensions.width - frame.width) / 2;
-        let padding_right = dimensions.width - frame.width - padding_left;
+        let free_width = dimensions.width - frame.width;
+        let free_height = dimensions.height - frame.height;
+        let origin = frame.origin_in(&dimensions);
+        let (padding_left, padding_top) = (origin.x, origin.y);
+        let padding_bottom = free_height - padding_top;
+        let padding_right = free_width - padding_left;
 
-        let mut frame_pixels = frame.pixel_data.into_iter();
+        let frame_pixel_data = match frame.alpha_data() {
+            // Translucent pixels are composited onto the letterbox background
+            Some(alpha_data) => frame
+                .pixel_data
+                .iter()
+                .zip(alpha_data)
+                .map(|(pixel, alpha)| blend(pixel, &self.background_color, *alpha))
+                .collect(),
+            None => frame.pixel_data,
+        };
+        let mut frame_pixels = frame_pixel_data.into_iter();
         let mut pixels = Vec::with_capacity((dimensions.height * dimensions.width) as usize);
         let mut bg_iter = std::iter::repeat(self.background_color.clone());
         // Push top padding
@@ -54,7 +78,13 @@ impl FrameFiller for LetterboxingDisplayFiller {
             anyhow!("not enough pixels in frame");
         }
 
-        display.update_pixels(pixels)?;
+        self.metrics.record_fill_duration(fill_started_at.elapsed());
+
+        let update_started_at = Instant::now();
+        let update_result = display.update_pixels(pixels);
+        self.metrics
+            .record_display_update_duration(update_started_at.elapsed());
+        update_result?;
         Ok(())
     }
 }
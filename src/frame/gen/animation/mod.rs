@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests;
+
+use crate::frame::gen::FrameGenerator;
+use crate::frame::Frame;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub struct Animation {
+    frames: Vec<(Frame, u128)>,
+    cycle_micros: u128,
+    loop_count: Option<u32>,
+}
+
+impl Animation {
+    pub fn new(frames: Vec<(Frame, Duration)>, loop_count: Option<u32>) -> Result<Self, String> {
+        if frames.is_empty() {
+            return Err("animation has no frames".to_string());
+        }
+        if loop_count == Some(0) {
+            return Err("animation must loop at least once".to_string());
+        }
+
+        let frames = frames
+            .into_iter()
+            .map(|(frame, duration)| (frame, duration.as_micros()))
+            .collect::<Vec<_>>();
+        let cycle_micros = frames.iter().map(|(_, duration)| duration).sum();
+        if cycle_micros == 0 {
+            return Err("animation has no duration".to_string());
+        }
+
+        Ok(Self {
+            frames,
+            cycle_micros,
+            loop_count,
+        })
+    }
+
+    pub fn frames(&self) -> impl Iterator<Item = &Frame> {
+        self.frames.iter().map(|(frame, _)| frame)
+    }
+
+    fn is_finished(&self, elapsed_micros: u128) -> bool {
+        self.loop_count
+            .is_some_and(|loop_count| elapsed_micros >= self.cycle_micros * loop_count as u128)
+    }
+
+    fn frame_at(&self, elapsed_micros: u128) -> &Frame {
+        let mut cycle_position = elapsed_micros % self.cycle_micros;
+        for (frame, duration) in self.frames.iter() {
+            if cycle_position < *duration {
+                return frame;
+            }
+            cycle_position -= duration;
+        }
+        unreachable!("cycle position is always within the cycle duration")
+    }
+}
+
+struct ScheduledAnimation {
+    start_unix_micros: u128,
+    animation: Animation,
+}
+
+impl ScheduledAnimation {
+    fn is_playing(&self, unix_micros: u128) -> bool {
+        unix_micros >= self.start_unix_micros
+            && !self
+                .animation
+                .is_finished(unix_micros - self.start_unix_micros)
+    }
+}
+
+#[derive(Default)]
+pub struct AnimationFrameGenerator {
+    animations: Mutex<BTreeMap<i8, ScheduledAnimation>>,
+}
+
+impl AnimationFrameGenerator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn play(&self, channel: i8, start_unix_micros: u128, animation: Animation) {
+        self.animations.lock().unwrap().insert(
+            channel,
+            ScheduledAnimation {
+                start_unix_micros,
+                animation,
+            },
+        );
+    }
+
+    pub fn cancel(&self, channel: i8) -> bool {
+        self.animations.lock().unwrap().remove(&channel).is_some()
+    }
+
+    pub fn active_channel(&self, unix_micros: u128) -> Option<i8> {
+        self.animations
+            .lock()
+            .unwrap()
+            .iter()
+            .rev()
+            .find(|(_, scheduled)| scheduled.is_playing(unix_micros))
+            .map(|(channel, _)| *channel)
+    }
+}
+
+impl FrameGenerator for AnimationFrameGenerator {
+    fn generate(&self, unix_micros: u128) -> Option<Frame> {
+        let mut animations_lock = self.animations.lock().unwrap();
+        animations_lock.retain(|_, scheduled| {
+            unix_micros < scheduled.start_unix_micros
+                || !scheduled
+                    .animation
+                    .is_finished(unix_micros - scheduled.start_unix_micros)
+        });
+
+        let (_, scheduled) = animations_lock
+            .iter()
+            .rev()
+            .find(|(_, scheduled)| scheduled.is_playing(unix_micros))?;
+        Some(
+            scheduled
+                .animation
+                .frame_at(unix_micros - scheduled.start_unix_micros)
+                .clone(),
+        )
+    }
+}
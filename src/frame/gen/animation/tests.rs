@@ -0,0 +1,65 @@
+use super::*;
+use crate::display::Pixel;
+
+fn colored_frame(r: u8) -> Frame {
+    Frame::with_color(1, 1, Pixel { r, g: 0, b: 0 })
+}
+
+fn two_frame_animation(loop_count: Option<u32>) -> Animation {
+    Animation::new(
+        vec![
+            (colored_frame(1), Duration::from_micros(100)),
+            (colored_frame(2), Duration::from_micros(300)),
+        ],
+        loop_count,
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_animation_cycles_through_frames() {
+    let gen = AnimationFrameGenerator::new();
+    gen.play(0, 1_000, two_frame_animation(None));
+
+    assert!(gen.generate(500).is_none());
+    assert!(gen.generate(1_000) == Some(colored_frame(1)));
+    assert!(gen.generate(1_100) == Some(colored_frame(2)));
+    assert!(gen.generate(1_399) == Some(colored_frame(2)));
+    assert!(gen.generate(1_400) == Some(colored_frame(1)));
+    assert!(gen.generate(1_001_000) == Some(colored_frame(1)));
+}
+
+#[test]
+fn test_animation_stops_after_loop_count() {
+    let gen = AnimationFrameGenerator::new();
+    gen.play(0, 0, two_frame_animation(Some(2)));
+
+    assert!(gen.generate(799) == Some(colored_frame(2)));
+    assert!(gen.generate(800).is_none());
+    assert_eq!(gen.active_channel(800), None);
+}
+
+#[test]
+fn test_animation_highest_channel_wins_until_cancelled() {
+    let gen = AnimationFrameGenerator::new();
+    gen.play(0, 0, two_frame_animation(None));
+    gen.play(
+        1,
+        0,
+        Animation::new(vec![(colored_frame(3), Duration::from_micros(10))], None).unwrap(),
+    );
+
+    assert_eq!(gen.active_channel(0), Some(1));
+    assert!(gen.generate(0) == Some(colored_frame(3)));
+
+    assert!(gen.cancel(1));
+    assert!(!gen.cancel(1));
+    assert!(gen.generate(0) == Some(colored_frame(1)));
+}
+
+#[test]
+fn test_animation_rejects_empty_or_zero_length() {
+    assert!(Animation::new(vec![], None).is_err());
+    assert!(Animation::new(vec![(colored_frame(1), Duration::ZERO)], None).is_err());
+    assert!(Animation::new(vec![(colored_frame(1), Duration::from_micros(1))], Some(0)).is_err());
+}
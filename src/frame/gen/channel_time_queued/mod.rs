@@ -1,11 +1,13 @@
 #[cfg(test)]
 mod tests;
 
+use crate::display::{Dimensions, Pixel, Position};
+use crate::event::{EventBus, FrameDropReason, StatusEvent};
 use crate::frame::gen::FrameGenerator;
-use crate::frame::Frame;
+use crate::frame::{Frame, Placement};
 use std::cmp::Ordering;
-use std::collections::{BTreeSet, BinaryHeap};
-use std::ops::Deref;
+use std::collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet};
+use std::ops::{Deref, RangeBounds};
 use std::sync::{Arc, Mutex};
 
 #[derive(PartialEq, Eq)]
@@ -13,6 +15,9 @@ pub struct ChannelTimedFrame {
     channel: i8,
     unix_micros: u128,
     frame: Frame,
+    patch_position: Option<Position>,
+    // Orders patches after the frame they apply to, in the order they were added
+    patch_sequence: u64,
 }
 
 impl PartialOrd<Self> for ChannelTimedFrame {
@@ -23,15 +28,52 @@ impl PartialOrd<Self> for ChannelTimedFrame {
 
 impl Ord for ChannelTimedFrame {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.unix_micros.cmp(&other.unix_micros)
+        self.unix_micros
+            .cmp(&other.unix_micros)
+            .then(self.channel.cmp(&other.channel))
+            .then(self.patch_sequence.cmp(&other.patch_sequence))
     }
 }
 
+/// How a channel is drawn when channels are composited instead of shown exclusively.
+#[derive(Clone, Copy)]
+pub struct Layer {
+    pub z_order: i32,
+    pub opacity: u8,
+}
+
+struct Compositing {
+    dimensions: Dimensions,
+    layers: HashMap<i8, Layer>,
+    // Time of the latest frame per channel, to tell which layers are still active
+    layer_updates: HashMap<i8, u128>,
+    composited_channels: Vec<i8>,
+}
+
+pub struct ChannelQueueStatus {
+    pub live_channel: Option<i8>,
+    pub queued_frames: BTreeMap<i8, usize>,
+    pub buffer_size: usize,
+    pub idle_seconds: f64,
+}
+
+pub struct PendingFrame {
+    pub channel: i8,
+    pub unix_micros: u128,
+    pub width: u32,
+    pub height: u32,
+    pub is_patch: bool,
+}
+
 pub struct ChannelTimeQueuedFrameGenerator {
     frames: Mutex<BTreeSet<ChannelTimedFrame>>,
     last_frame_meta: Mutex<Option<(i8, u128)>>,
+    channel_frames: Mutex<HashMap<i8, Frame>>,
     buffer_size: usize,
     idle_seconds: f64,
+    channel_idle_seconds: HashMap<i8, f64>,
+    compositing: Option<Mutex<Compositing>>,
+    events: EventBus,
 }
 
 impl ChannelTimeQueuedFrameGenerator {
@@ -41,51 +83,363 @@ impl ChannelTimeQueuedFrameGenerator {
         Self {
             frames: Mutex::new(buffer),
             last_frame_meta: Mutex::new(None),
+            channel_frames: Mutex::new(HashMap::new()),
             buffer_size,
             idle_seconds,
+            channel_idle_seconds: HashMap::new(),
+            compositing: None,
+            events: EventBus::default(),
         }
     }
 
-    pub fn add_frame(&self, channel: i8, unix_micros: u128, frame: Frame) {
+    pub fn with_channel_idle_seconds(mut self, channel_idle_seconds: HashMap<i8, f64>) -> Self {
+        self.channel_idle_seconds = channel_idle_seconds;
+        self
+    }
+
+    /// Blends the latest frames of all active channels into frames of the given dimensions,
+    /// channels without a layer are drawn opaque and ordered by their index.
+    pub fn with_compositing(mut self, dimensions: Dimensions, layers: HashMap<i8, Layer>) -> Self {
+        self.compositing = Some(Mutex::new(Compositing {
+            dimensions,
+            layers,
+            layer_updates: HashMap::new(),
+            composited_channels: Vec::new(),
+        }));
+        self
+    }
+
+    fn idle_micros(&self, channel: i8) -> u128 {
+        let idle_seconds = self
+            .channel_idle_seconds
+            .get(&channel)
+            .copied()
+            .unwrap_or(self.idle_seconds);
+        (idle_seconds * 1_000_000.0) as u128
+    }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    /// Returns whether the frame will be shown, superseded frames are still queued in case the
+    /// frames hiding them are removed.
+    pub fn add_frame(&self, channel: i8, unix_micros: u128, frame: Frame) -> bool {
+        let mut frames_lock = self.frames.lock().unwrap();
+        let is_superseded = self.is_superseded_in(&frames_lock, channel, unix_micros);
+        let is_inserted = self.insert_frame(
+            &mut frames_lock,
+            ChannelTimedFrame {
+                channel,
+                unix_micros,
+                frame,
+                patch_position: None,
+                patch_sequence: 0,
+            },
+        );
+        is_inserted && !is_superseded
+    }
+
+    pub fn add_frames(&self, channel: i8, frames: Vec<(u128, Frame)>) -> Vec<bool> {
         let mut frames_lock = self.frames.lock().unwrap();
-        while frames_lock.len() >= self.buffer_size {
-            frames_lock.pop_last();
+        let batch_times = frames
+            .iter()
+            .map(|(unix_micros, _)| *unix_micros)
+            .collect::<Vec<_>>();
+        let mut accepted = frames
+            .into_iter()
+            .map(|(unix_micros, frame)| {
+                if self.is_superseded_in(&frames_lock, channel, unix_micros) {
+                    return false;
+                }
+                self.insert_frame(
+                    &mut frames_lock,
+                    ChannelTimedFrame {
+                        channel,
+                        unix_micros,
+                        frame,
+                        patch_position: None,
+                        patch_sequence: 0,
+                    },
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // Later frames of the batch may have evicted or replaced earlier ones
+        let queued_times = frames_lock
+            .iter()
+            .filter(|frame| frame.channel == channel && frame.patch_position.is_none())
+            .map(|frame| frame.unix_micros)
+            .collect::<HashSet<_>>();
+        let mut replacing_times = HashSet::new();
+        for (is_accepted, unix_micros) in accepted.iter_mut().zip(batch_times).rev() {
+            if !*is_accepted {
+                continue;
+            }
+            *is_accepted =
+                queued_times.contains(&unix_micros) && !replacing_times.contains(&unix_micros);
+            replacing_times.insert(unix_micros);
         }
+        accepted
+    }
 
-        let candidate = ChannelTimedFrame {
-            channel,
-            unix_micros,
-            frame,
-        };
-        if let Some(collision) = frames_lock.get(&candidate) {
-            if collision.channel > channel {
-                return;
+    /// Returns `None` without a frame to patch, whether the patch will be shown otherwise.
+    pub fn add_patch(
+        &self,
+        channel: i8,
+        unix_micros: u128,
+        patch: Frame,
+        position: Position,
+    ) -> Option<bool> {
+        let mut frames_lock = self.frames.lock().unwrap();
+        let has_base_frame = self.channel_frames.lock().unwrap().contains_key(&channel)
+            || frames_lock.iter().any(|frame| {
+                frame.channel == channel
+                    && frame.unix_micros <= unix_micros
+                    && frame.patch_position.is_none()
+            });
+        if !has_base_frame {
+            return None;
+        }
+
+        // Patches never replace the frame queued at the same time, they're applied after it
+        let patch_sequence = frames_lock
+            .iter()
+            .filter(|frame| frame.unix_micros == unix_micros && frame.channel == channel)
+            .map(|frame| frame.patch_sequence)
+            .max()
+            .map_or(1, |sequence| sequence + 1);
+        let is_superseded = self.is_superseded_in(&frames_lock, channel, unix_micros);
+        let is_inserted = self.insert_frame(
+            &mut frames_lock,
+            ChannelTimedFrame {
+                channel,
+                unix_micros,
+                frame: patch,
+                patch_position: Some(position),
+                patch_sequence,
+            },
+        );
+        Some(is_inserted && !is_superseded)
+    }
+
+    fn insert_frame(
+        &self,
+        frames: &mut BTreeSet<ChannelTimedFrame>,
+        candidate: ChannelTimedFrame,
+    ) -> bool {
+        while frames.len() >= self.buffer_size {
+            if let Some(evicted) = frames.pop_last() {
+                self.emit_dropped(&evicted, FrameDropReason::Evicted);
+            }
+        }
+
+        // Without compositing only one channel can be shown at a time, the highest one wins
+        if self.compositing.is_none() {
+            let mut collisions = frames
+                .iter()
+                .skip_while(|frame| frame.unix_micros < candidate.unix_micros)
+                .take_while(|frame| frame.unix_micros == candidate.unix_micros);
+            if collisions.any(|frame| frame.channel > candidate.channel) {
+                self.emit_dropped(&candidate, FrameDropReason::Superseded);
+                return false;
             }
+            frames.retain(|frame| {
+                let is_hidden =
+                    frame.unix_micros == candidate.unix_micros && frame.channel < candidate.channel;
+                if is_hidden {
+                    self.emit_dropped(frame, FrameDropReason::Superseded);
+                }
+                !is_hidden
+            });
         }
-        frames_lock.replace(candidate);
+        if let Some(replaced) = frames.replace(candidate) {
+            self.emit_dropped(&replaced, FrameDropReason::Superseded);
+        }
+        true
+    }
+
+    fn emit_dropped(&self, frame: &ChannelTimedFrame, reason: FrameDropReason) {
+        self.events.emit(StatusEvent::FrameDropped {
+            channel: frame.channel,
+            unix_micros: frame.unix_micros,
+            reason,
+        });
     }
 
     pub fn is_frame_superseded(&self, channel: i8, unix_micros: u128) -> bool {
         let frames_lock = self.frames.lock().unwrap();
-        for frame in frames_lock.iter() {
+        self.is_superseded_in(&frames_lock, channel, unix_micros)
+    }
+
+    fn is_superseded_in(
+        &self,
+        frames: &BTreeSet<ChannelTimedFrame>,
+        channel: i8,
+        unix_micros: u128,
+    ) -> bool {
+        // Layers are drawn together, so no channel hides another
+        if self.compositing.is_some() {
+            return false;
+        }
+
+        for frame in frames.iter() {
             if frame.unix_micros > unix_micros {
                 break;
             }
 
             if frame.channel > channel
-                && frame.unix_micros + (self.idle_seconds * 1_000_000.0) as u128 > unix_micros
+                && frame.unix_micros + self.idle_micros(frame.channel) > unix_micros
             {
                 return true;
             }
         }
         false
     }
+
+    pub fn pending_frames(&self, channel: Option<i8>) -> Vec<PendingFrame> {
+        let frames_lock = self.frames.lock().unwrap();
+        frames_lock
+            .iter()
+            .filter(|frame| channel.is_none_or(|channel| frame.channel == channel))
+            .map(|frame| PendingFrame {
+                channel: frame.channel,
+                unix_micros: frame.unix_micros,
+                width: frame.frame.width,
+                height: frame.frame.height,
+                is_patch: frame.patch_position.is_some(),
+            })
+            .collect()
+    }
+
+    pub fn remove_frames(&self, channel: Option<i8>, range: impl RangeBounds<u128>) -> usize {
+        let mut frames_lock = self.frames.lock().unwrap();
+        let frame_count = frames_lock.len();
+        frames_lock.retain(|frame| {
+            !(channel.is_none_or(|channel| frame.channel == channel)
+                && range.contains(&frame.unix_micros))
+        });
+        frame_count - frames_lock.len()
+    }
+
+    pub fn live_channel(&self, unix_micros: u128) -> Option<i8> {
+        self.last_frame_meta
+            .lock()
+            .unwrap()
+            .filter(|(last_channel, last_micros)| {
+                last_micros + self.idle_micros(*last_channel) > unix_micros
+            })
+            .map(|(channel, _)| channel)
+    }
+
+    pub fn status(&self, unix_micros: u128) -> ChannelQueueStatus {
+        let frames_lock = self.frames.lock().unwrap();
+        let mut queued_frames = BTreeMap::new();
+        for frame in frames_lock.iter() {
+            *queued_frames.entry(frame.channel).or_insert(0) += 1;
+        }
+        drop(frames_lock);
+
+        ChannelQueueStatus {
+            live_channel: self.live_channel(unix_micros),
+            queued_frames,
+            buffer_size: self.buffer_size,
+            idle_seconds: self.idle_seconds,
+        }
+    }
+}
+
+impl ChannelTimeQueuedFrameGenerator {
+    fn generate_composited(
+        &self,
+        compositing: &Mutex<Compositing>,
+        unix_micros: u128,
+    ) -> Option<Frame> {
+        let mut frames_lock = self.frames.lock().unwrap();
+        let mut last_frame_meta = self.last_frame_meta.lock().unwrap();
+        let mut channel_frames = self.channel_frames.lock().unwrap();
+        let mut compositing = compositing.lock().unwrap();
+        let Compositing {
+            dimensions,
+            layers,
+            layer_updates,
+            composited_channels,
+        } = &mut *compositing;
+        let mut updated_frames: HashMap<i8, ChannelTimedFrame> = HashMap::new();
+
+        while let Some(current) = frames_lock.pop_first() {
+            if current.unix_micros > unix_micros {
+                frames_lock.insert(current);
+                break;
+            }
+
+            let current = match resolve_patch(&mut channel_frames, current) {
+                Ok(current) => current,
+                Err(patch) => {
+                    self.emit_dropped(&patch, FrameDropReason::MissingPatchBase);
+                    continue;
+                }
+            };
+            layer_updates.insert(current.channel, current.unix_micros);
+            if let Some(skipped) = updated_frames.insert(current.channel, current) {
+                self.emit_dropped(&skipped, FrameDropReason::Superseded);
+            }
+        }
+
+        let mut active_channels = layer_updates
+            .iter()
+            .filter(|(channel, updated_micros)| {
+                *updated_micros + self.idle_micros(**channel) > unix_micros
+            })
+            .map(|(channel, _)| *channel)
+            .collect::<Vec<_>>();
+        let layer = |channel: i8| {
+            layers.get(&channel).copied().unwrap_or(Layer {
+                z_order: channel as i32,
+                opacity: u8::MAX,
+            })
+        };
+        active_channels.sort_by_key(|channel| (layer(*channel).z_order, *channel));
+
+        // Only composite when a layer changed or appeared or expired since the last frame
+        if updated_frames.is_empty() && active_channels == *composited_channels {
+            return None;
+        }
+        if let Some(top_channel) = active_channels.last() {
+            *last_frame_meta = Some((*top_channel, layer_updates[top_channel]));
+        }
+        *composited_channels = active_channels.clone();
+        if active_channels.is_empty() {
+            return None;
+        }
+
+        let pixel_count = (dimensions.width * dimensions.height) as usize;
+        let black = Pixel { r: 0, g: 0, b: 0 };
+        let mut composited = Frame::with_color(dimensions.width, dimensions.height, black)
+            .with_alpha(vec![0; pixel_count])
+            .ok()?;
+        for channel in active_channels {
+            let Some(frame) = channel_frames.get(&channel) else {
+                continue;
+            };
+            let origin = frame.origin_in(dimensions);
+            let frame = frame.clone().with_opacity(layer(channel).opacity);
+            composited = composited.patched(&frame, &origin);
+        }
+        Some(composited.with_placement(Placement::TopLeft))
+    }
 }
 
 impl FrameGenerator for ChannelTimeQueuedFrameGenerator {
     fn generate(&self, unix_micros: u128) -> Option<Frame> {
+        if let Some(compositing) = &self.compositing {
+            return self.generate_composited(compositing, unix_micros);
+        }
+
         let mut frames_lock = self.frames.lock().unwrap();
         let mut last_frame_meta = self.last_frame_meta.lock().unwrap();
+        let mut channel_frames = self.channel_frames.lock().unwrap();
         let mut candidate: Option<ChannelTimedFrame> = None;
 
         while let Some(current) = frames_lock.pop_first() {
@@ -94,24 +448,38 @@ impl FrameGenerator for ChannelTimeQueuedFrameGenerator {
                 break;
             }
 
+            // Patches are applied even if the channel is currently hidden, so the
+            // channel shows up to date content once it becomes visible again
+            let current = match resolve_patch(&mut channel_frames, current) {
+                Ok(current) => current,
+                Err(patch) => {
+                    self.emit_dropped(&patch, FrameDropReason::MissingPatchBase);
+                    continue;
+                }
+            };
+
             if let Some((last_channel, last_micros)) = last_frame_meta.deref() {
                 if *last_channel > current.channel
-                    && last_micros + (self.idle_seconds * 1_000_000.0) as u128 > current.unix_micros
+                    && last_micros + self.idle_micros(*last_channel) > current.unix_micros
                 {
+                    self.emit_dropped(&current, FrameDropReason::Superseded);
                     continue;
                 }
             }
 
             if let Some(candidate) = &candidate {
                 if candidate.channel > current.channel
-                    && candidate.unix_micros + (self.idle_seconds * 1_000_000.0) as u128
+                    && candidate.unix_micros + self.idle_micros(candidate.channel)
                         > current.unix_micros
                 {
+                    self.emit_dropped(&current, FrameDropReason::Superseded);
                     continue;
                 }
             }
 
-            candidate = Some(current);
+            if let Some(skipped) = candidate.replace(current) {
+                self.emit_dropped(&skipped, FrameDropReason::Superseded);
+            }
         }
 
         if let Some(meta) = &candidate {
@@ -119,10 +487,54 @@ impl FrameGenerator for ChannelTimeQueuedFrameGenerator {
         }
         candidate.map(|x| x.frame)
     }
+
+    // Channels stay live for their idle time, layers stay active while composited
+    fn is_active(&self, unix_micros: u128) -> bool {
+        match &self.compositing {
+            Some(compositing) => {
+                compositing
+                    .lock()
+                    .unwrap()
+                    .layer_updates
+                    .iter()
+                    .any(|(channel, updated_micros)| {
+                        updated_micros + self.idle_micros(*channel) > unix_micros
+                    })
+            }
+            None => self.live_channel(unix_micros).is_some(),
+        }
+    }
+}
+
+/// Returns patches without a frame to apply to as errors.
+fn resolve_patch(
+    channel_frames: &mut HashMap<i8, Frame>,
+    timed_frame: ChannelTimedFrame,
+) -> Result<ChannelTimedFrame, ChannelTimedFrame> {
+    let frame = match &timed_frame.patch_position {
+        Some(position) => match channel_frames.get(&timed_frame.channel) {
+            Some(base) => base.patched(&timed_frame.frame, position),
+            None => return Err(timed_frame),
+        },
+        None => timed_frame.frame,
+    };
+    channel_frames.insert(timed_frame.channel, frame.clone());
+
+    Ok(ChannelTimedFrame {
+        channel: timed_frame.channel,
+        unix_micros: timed_frame.unix_micros,
+        frame,
+        patch_position: None,
+        patch_sequence: 0,
+    })
 }
 
 impl FrameGenerator for Arc<ChannelTimeQueuedFrameGenerator> {
     fn generate(&self, unix_micros: u128) -> Option<Frame> {
         ChannelTimeQueuedFrameGenerator::generate(self, unix_micros)
     }
+
+    fn is_active(&self, unix_micros: u128) -> bool {
+        ChannelTimeQueuedFrameGenerator::is_active(self, unix_micros)
+    }
 }
@@ -1,4 +1,5 @@
 use super::*;
+use crate::display::Pixel;
 
 
 
@@ -40,3 +41,281 @@ fn test_obsolete_with_same_channel_false_when_inside_range() {
     assert!(!gen.is_frame_superseded(1, 50));
     assert!(!gen.is_frame_superseded(1, 150));
 }
+
+#[test]
+fn test_status_counts_queued_frames_per_channel() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::empty());
+    gen.add_frame(0, 200, Frame::empty());
+    gen.add_frame(2, 300, Frame::empty());
+
+    let status = gen.status(0);
+    assert_eq!(status.queued_frames.get(&0), Some(&2));
+    assert_eq!(status.queued_frames.get(&2), Some(&1));
+    assert_eq!(status.live_channel, None);
+}
+
+#[test]
+fn test_status_live_channel_expires_after_idle() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(3, 100, Frame::empty());
+    gen.generate(100);
+
+    assert_eq!(gen.status(200).live_channel, Some(3));
+    assert_eq!(gen.status(1_000_000 + 200).live_channel, None);
+    assert!(gen.status(200).queued_frames.is_empty());
+}
+
+#[test]
+fn test_patch_without_base_frame_rejected() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(1, 100, Frame::empty());
+
+    assert_eq!(
+        gen.add_patch(0, 200, Frame::empty(), Position { x: 0, y: 0 }),
+        None
+    );
+}
+
+#[test]
+fn test_patch_composited_onto_last_channel_frame() {
+    let black = Pixel { r: 0, g: 0, b: 0 };
+    let white = Pixel {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::with_color(3, 2, black.clone()));
+    gen.generate(100);
+
+    assert_eq!(
+        gen.add_patch(
+            0,
+            200,
+            Frame::with_color(2, 2, white.clone()),
+            Position { x: 2, y: 1 }
+        ),
+        Some(true)
+    );
+    let frame = gen.generate(200).unwrap();
+
+    assert_eq!(frame.dimensions().width, 3);
+    assert_eq!(frame.dimensions().height, 2);
+    assert!(frame.pixel_data()[..5].iter().all(|pixel| *pixel == black));
+    assert!(frame.pixel_data()[5] == white);
+}
+
+#[test]
+fn test_patch_at_same_time_applied_after_base_frame() {
+    let black = Pixel { r: 0, g: 0, b: 0 };
+    let white = Pixel {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::with_color(2, 1, black.clone()));
+
+    let patch = || Frame::with_color(1, 1, white.clone());
+    assert_eq!(
+        gen.add_patch(0, 100, patch(), Position { x: 0, y: 0 }),
+        Some(true)
+    );
+    assert_eq!(
+        gen.add_patch(0, 100, patch(), Position { x: 1, y: 0 }),
+        Some(true)
+    );
+    let frame = gen.generate(100).unwrap();
+
+    assert!(frame.pixel_data().iter().all(|pixel| *pixel == white));
+}
+
+#[test]
+fn test_patch_without_remaining_base_frame_reported() {
+    let events = EventBus::default();
+    let mut receiver = events.subscribe();
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0).with_events(events);
+    gen.add_frame(0, 100, Frame::empty());
+    gen.add_patch(0, 150, Frame::empty(), Position { x: 0, y: 0 });
+    gen.add_frame(1, 100, Frame::empty());
+    gen.generate(200);
+
+    let reasons = std::iter::from_fn(|| receiver.try_recv().ok())
+        .filter_map(|event| match event {
+            StatusEvent::FrameDropped {
+                unix_micros,
+                reason,
+                ..
+            } => Some((unix_micros, reason)),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert!(matches!(
+        reasons.as_slice(),
+        [
+            (100, FrameDropReason::Superseded),
+            (150, FrameDropReason::MissingPatchBase)
+        ]
+    ));
+}
+
+#[test]
+fn test_add_frames_reports_superseded_frames() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(1, 100, Frame::empty());
+
+    let accepted = gen.add_frames(
+        0,
+        vec![
+            (50, Frame::empty()),
+            (150, Frame::empty()),
+            (1_000_000 + 200, Frame::empty()),
+        ],
+    );
+
+    assert_eq!(accepted, vec![true, false, true]);
+    assert_eq!(gen.status(0).queued_frames.get(&0), Some(&2));
+}
+
+#[test]
+fn test_add_frames_reports_frames_dropped_by_later_frames() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2, 1.0);
+
+    let accepted = gen.add_frames(
+        0,
+        vec![
+            (100, Frame::empty()),
+            (200, Frame::empty()),
+            (300, Frame::empty()),
+        ],
+    );
+    assert_eq!(accepted, vec![true, false, true]);
+
+    let accepted = gen.add_frames(1, vec![(400, Frame::empty()), (400, Frame::empty())]);
+    assert_eq!(accepted, vec![false, true]);
+}
+
+#[test]
+fn test_add_frame_reports_superseded_frames() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    assert!(gen.add_frame(1, 100, Frame::empty()));
+
+    assert!(gen.add_frame(0, 50, Frame::empty()));
+    assert!(!gen.add_frame(0, 150, Frame::empty()));
+    assert!(!gen.add_frame(0, 100, Frame::empty()));
+}
+
+#[test]
+fn test_pending_frames_filtered_by_channel() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::with_color(2, 1, Pixel { r: 0, g: 0, b: 0 }));
+    gen.add_frame(1, 200, Frame::empty());
+
+    let pending = gen.pending_frames(Some(0));
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].unix_micros, 100);
+    assert_eq!(pending[0].width, 2);
+    assert_eq!(gen.pending_frames(None).len(), 2);
+}
+
+#[test]
+fn test_remove_frames_by_channel_and_range() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::empty());
+    gen.add_frame(0, 200, Frame::empty());
+    gen.add_frame(0, 300, Frame::empty());
+    gen.add_frame(1, 400, Frame::empty());
+
+    assert_eq!(gen.remove_frames(Some(0), 200..), 2);
+    assert_eq!(gen.remove_frames(None, ..), 2);
+    assert!(gen.pending_frames(None).is_empty());
+}
+
+#[test]
+fn test_obsolete_uses_idle_seconds_of_higher_channel() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0)
+        .with_channel_idle_seconds(HashMap::from([(1, 0.5)]));
+    gen.add_frame(1, 100, Frame::empty());
+
+    assert!(gen.is_frame_superseded(0, 400_000));
+    assert!(!gen.is_frame_superseded(0, 600_000));
+}
+
+#[test]
+fn test_same_time_frame_of_higher_channel_wins() {
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0);
+    gen.add_frame(0, 100, Frame::with_color(1, 1, Pixel { r: 0, g: 0, b: 0 }));
+    gen.add_frame(1, 100, Frame::with_color(2, 1, Pixel { r: 0, g: 0, b: 0 }));
+    gen.add_frame(0, 100, Frame::with_color(3, 1, Pixel { r: 0, g: 0, b: 0 }));
+
+    let pending = gen.pending_frames(None);
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[0].channel, 1);
+}
+
+#[test]
+fn test_compositing_blends_layers_by_opacity() {
+    let red = Pixel { r: 255, g: 0, b: 0 };
+    let white = Pixel {
+        r: 255,
+        g: 255,
+        b: 255,
+    };
+    let dimensions = Dimensions {
+        width: 2,
+        height: 1,
+    };
+    let layers = HashMap::from([(
+        1,
+        Layer {
+            z_order: 1,
+            opacity: 128,
+        },
+    )]);
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0).with_compositing(dimensions, layers);
+    gen.add_frame(0, 100, Frame::with_color(2, 1, red.clone()));
+    gen.add_frame(
+        1,
+        100,
+        Frame::with_color(1, 1, white).with_placement(Placement::TopLeft),
+    );
+
+    let frame = gen.generate(100).unwrap();
+    assert!(
+        frame.pixel_data()[0]
+            == Pixel {
+                r: 255,
+                g: 128,
+                b: 128
+            }
+    );
+    assert!(frame.pixel_data()[1] == red);
+    assert_eq!(frame.alpha_data(), Some(&vec![255, 255]));
+    assert_eq!(gen.live_channel(100), Some(1));
+    assert!(gen.generate(200).is_none());
+}
+
+#[test]
+fn test_compositing_drops_idle_layers() {
+    let black = Pixel { r: 0, g: 0, b: 0 };
+    let dimensions = Dimensions {
+        width: 1,
+        height: 1,
+    };
+    let gen = ChannelTimeQueuedFrameGenerator::new(2500, 1.0)
+        .with_channel_idle_seconds(HashMap::from([(1, 0.5)]))
+        .with_compositing(dimensions, HashMap::new());
+    gen.add_frame(0, 100, Frame::with_color(1, 1, black.clone()));
+    gen.add_frame(1, 100, Frame::with_color(1, 1, black));
+    assert!(!gen.is_frame_superseded(0, 200));
+    gen.generate(100);
+
+    let frame = gen.generate(600_000).unwrap();
+    assert_eq!(frame.alpha_data(), Some(&vec![255]));
+    assert_eq!(gen.live_channel(600_000), Some(0));
+
+    let frame = gen.generate(1_000_100);
+    assert!(frame.is_none());
+    assert_eq!(gen.live_channel(1_000_100), None);
+}
@@ -1,13 +1,22 @@
+use crate::event::{EventBus, StatusEvent};
 use crate::frame::gen::FrameGenerator;
 use crate::frame::Frame;
+use crate::metrics::Metrics;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Mutex;
 use std::time::Duration;
 
+#[cfg(test)]
+mod tests;
+
 pub struct FallbackFrameGenerator {
     base_generator: Box<dyn FrameGenerator>,
     fallback_generator: Box<dyn FrameGenerator>,
     idle_duration_micros: u128,
     last_frame_instant: Mutex<Option<u128>>,
+    fallback_active: AtomicBool,
+    events: EventBus,
+    metrics: Metrics,
 }
 
 impl FallbackFrameGenerator {
@@ -21,8 +30,21 @@ impl FallbackFrameGenerator {
             fallback_generator: Box::new(fallback_generator),
             idle_duration_micros: idle_duration.as_micros(),
             last_frame_instant: Mutex::new(None),
+            fallback_active: AtomicBool::new(false),
+            events: EventBus::default(),
+            metrics: Metrics::default(),
         }
     }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
 }
 
 impl FrameGenerator for FallbackFrameGenerator {
@@ -30,9 +52,15 @@ impl FrameGenerator for FallbackFrameGenerator {
         let base_frame = self.base_generator.generate(unix_micros);
         if let Some(base_frame) = base_frame {
             *self.last_frame_instant.lock().unwrap() = Some(unix_micros);
+            self.fallback_active.store(false, Ordering::Relaxed);
             return Some(base_frame);
         }
 
+        // Channels may stay live longer than the idle duration, their last frame stays up until then
+        if self.base_generator.is_active(unix_micros) {
+            return None;
+        }
+
         let last_frame_instant = self.last_frame_instant.lock().unwrap();
         if let Some(last_frame_instant) = last_frame_instant.as_ref() {
             if unix_micros - *last_frame_instant < self.idle_duration_micros {
@@ -40,6 +68,10 @@ impl FrameGenerator for FallbackFrameGenerator {
             }
         }
 
+        if !self.fallback_active.swap(true, Ordering::Relaxed) {
+            self.events.emit(StatusEvent::FallbackActivated);
+            self.metrics.record_fallback_activation();
+        }
         self.fallback_generator.generate(unix_micros)
     }
 }
@@ -0,0 +1,50 @@
+use super::*;
+use crate::display::{Dimensions, Pixel};
+use crate::frame::gen::channel_time_queued::ChannelTimeQueuedFrameGenerator;
+use crate::frame::gen::solid_color::SolidColorFrameGenerator;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const SECOND_MICROS: u128 = 1_000_000;
+
+fn fallback(base: Arc<ChannelTimeQueuedFrameGenerator>) -> FallbackFrameGenerator {
+    FallbackFrameGenerator::new(
+        base,
+        SolidColorFrameGenerator::new(Pixel { r: 0, g: 0, b: 0 }, 1, 1),
+        Duration::from_secs(1),
+    )
+}
+
+#[test]
+fn test_fallback_waits_for_channel_idle_time() {
+    let base = Arc::new(
+        ChannelTimeQueuedFrameGenerator::new(2500, 1.0)
+            .with_channel_idle_seconds(HashMap::from([(1, 5.0)])),
+    );
+    let gen = fallback(Arc::clone(&base));
+    base.add_frame(1, 0, Frame::empty());
+
+    assert!(gen.generate(0).is_some());
+    assert!(gen.generate(2 * SECOND_MICROS).is_none());
+    assert!(gen.generate(4 * SECOND_MICROS).is_none());
+    assert!(gen.generate(5 * SECOND_MICROS).is_some());
+}
+
+#[test]
+fn test_fallback_waits_for_active_layers() {
+    let dimensions = Dimensions {
+        width: 1,
+        height: 1,
+    };
+    let base = Arc::new(
+        ChannelTimeQueuedFrameGenerator::new(2500, 1.0)
+            .with_channel_idle_seconds(HashMap::from([(0, 3.0)]))
+            .with_compositing(dimensions, HashMap::new()),
+    );
+    let gen = fallback(Arc::clone(&base));
+    base.add_frame(0, 0, Frame::with_color(1, 1, Pixel { r: 9, g: 9, b: 9 }));
+
+    assert!(gen.generate(0).is_some());
+    assert!(gen.generate(2 * SECOND_MICROS).is_none());
+    assert!(gen.generate(3 * SECOND_MICROS).is_some());
+}
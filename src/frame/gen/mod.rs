@@ -1,3 +1,4 @@
+pub mod animation;
 pub mod channel_time_queued;
 pub mod fallback;
 pub mod solid_color;
@@ -8,10 +9,19 @@ use crate::frame::Frame;
 
 pub trait FrameGenerator {
     fn generate(&self, unix_micros: u128) -> Option<Frame>;
+
+    /// Whether previously generated content is still meant to be shown, even without a new frame.
+    fn is_active(&self, _unix_micros: u128) -> bool {
+        false
+    }
 }
 
 impl<T: FrameGenerator + ?Sized> FrameGenerator for Box<T> {
     fn generate(&self, unix_micros: u128) -> Option<Frame> {
         (**self).generate(unix_micros)
     }
+
+    fn is_active(&self, unix_micros: u128) -> bool {
+        (**self).is_active(unix_micros)
+    }
 }
@@ -1,67 +1,246 @@
-use crate::frame::gen::channel_time_queued::ChannelTimeQueuedFrameGenerator;
+use crate::display::{Dimensions, Position};
+use crate::event::{EventBus, StatusEvent};
+use crate::frame::gen::animation::{Animation, AnimationFrameGenerator};
+use crate::frame::gen::channel_time_queued::{ChannelTimeQueuedFrameGenerator, Layer};
 use crate::frame::gen::time_queued::TimeQueuedFrameGenerator;
 use crate::frame::gen::FrameGenerator;
-use crate::frame::Frame;
+use crate::frame::{Frame, Placement};
+use crate::metrics::Metrics;
 use crate::web;
-use crate::web::{WebServerConfig, WebServerControl};
-use std::sync::Arc;
+use crate::web::{
+    ChannelInfo, DisplayControl, FrameAcceptance, FrameRejection, QueueStatus, QueuedFrame,
+    WebServerConfig, WebServerControl,
+};
+use std::ops::Bound;
+use std::sync::{Arc, Mutex};
 use tokio::runtime::Handle;
 use tokio::task;
 
+#[cfg(test)]
+mod tests;
+
 #[derive(Clone)]
 pub struct WebQueriedFrameGeneratorConfig {
     pub channel_idle_seconds: f64,
     pub display_width: u32,
     pub display_height: u32,
     pub display_fps: f64,
+    pub display_driver: String,
+    pub channels: Vec<ChannelInfo>,
+    pub compositing: bool,
 }
 
 pub struct WebQueriedFrameGenerator {
     config: WebQueriedFrameGeneratorConfig,
     time_queued_frame_generator: Arc<ChannelTimeQueuedFrameGenerator>,
+    animation_frame_generator: Arc<AnimationFrameGenerator>,
+    displayed_channel: Mutex<Option<i8>>,
+    events: EventBus,
+    metrics: Metrics,
     server_join_handles: Vec<task::JoinHandle<()>>,
 }
 
 impl WebQueriedFrameGenerator {
-    pub fn new(config: WebQueriedFrameGeneratorConfig) -> Self {
-        let generator = ChannelTimeQueuedFrameGenerator::new(2_500, config.channel_idle_seconds);
+    pub fn new(config: WebQueriedFrameGeneratorConfig, events: EventBus) -> Self {
+        let channel_idle_seconds = config
+            .channels
+            .iter()
+            .filter_map(|channel| Some((channel.priority, channel.idle_seconds?)))
+            .collect();
+        let mut generator =
+            ChannelTimeQueuedFrameGenerator::new(2_500, config.channel_idle_seconds)
+                .with_channel_idle_seconds(channel_idle_seconds)
+                .with_events(events.clone());
+        if config.compositing {
+            let layers = config
+                .channels
+                .iter()
+                .map(|channel| {
+                    let layer = Layer {
+                        z_order: channel.z_order.unwrap_or(channel.priority as i32),
+                        opacity: (channel.opacity.unwrap_or(1.0) * u8::MAX as f64).round() as u8,
+                    };
+                    (channel.priority, layer)
+                })
+                .collect();
+            let dimensions = Dimensions {
+                width: config.display_width,
+                height: config.display_height,
+            };
+            generator = generator.with_compositing(dimensions, layers);
+        }
 
         Self {
             config,
             time_queued_frame_generator: Arc::new(generator),
+            animation_frame_generator: Arc::new(AnimationFrameGenerator::new()),
+            displayed_channel: Mutex::new(None),
+            events,
+            metrics: Metrics::default(),
             server_join_handles: vec![],
         }
     }
 
-    pub fn start_server(&mut self, config: WebServerConfig) {
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    pub fn start_server(&mut self, config: WebServerConfig, display_control: DisplayControl) {
         let server_control = WebServerControl {
             display_width: self.config.display_width,
             display_height: self.config.display_height,
             display_fps: self.config.display_fps,
+            display_driver: self.config.display_driver.clone(),
+            channels: self.config.channels.clone(),
+            events: self.events.clone(),
+            metrics: self.metrics.clone(),
+            display: display_control,
             on_frame_received: Box::new({
                 let framed_generator = Arc::clone(&self.time_queued_frame_generator);
                 let gen_config = self.config.clone();
                 move |event| {
-                    if event.frame.width > gen_config.display_width
-                        || event.frame.height > gen_config.display_height
-                    {
-                        return Err("frame too large".to_string());
+                    let channel = event.channel.unwrap_or(0);
+                    let validation = validate_frame(
+                        &gen_config,
+                        channel,
+                        &event.frame,
+                        event.patch_position.as_ref(),
+                    );
+                    if let Err(err) = validation {
+                        return FrameAcceptance::Rejected(err);
                     }
 
-                    framed_generator.add_frame(
-                        event.channel.unwrap_or(0),
-                        event.unix_micros,
-                        event.frame,
-                    );
-                    Ok(())
+                    let is_shown = match event.patch_position {
+                        Some(position) => {
+                            match framed_generator.add_patch(
+                                channel,
+                                event.unix_micros,
+                                event.frame,
+                                position,
+                            ) {
+                                Some(is_shown) => is_shown,
+                                None => {
+                                    return FrameAcceptance::Rejected(
+                                        FrameRejection::MissingPatchBase,
+                                    )
+                                }
+                            }
+                        }
+                        None => framed_generator.add_frame(channel, event.unix_micros, event.frame),
+                    };
+                    if is_shown {
+                        FrameAcceptance::Accepted
+                    } else {
+                        FrameAcceptance::Superseded
+                    }
+                }
+            }),
+            on_frames_received: Box::new({
+                let framed_generator = Arc::clone(&self.time_queued_frame_generator);
+                let gen_config = self.config.clone();
+                move |event| {
+                    let channel = event.channel.unwrap_or(0);
+                    let validations = event
+                        .frames
+                        .iter()
+                        .map(|received| validate_frame(&gen_config, channel, &received.frame, None))
+                        .collect::<Vec<_>>();
+                    let valid_frames = event
+                        .frames
+                        .into_iter()
+                        .zip(validations.iter())
+                        .filter(|(_, validation)| validation.is_ok())
+                        .map(|(received, _)| (received.unix_micros, received.frame))
+                        .collect();
+
+                    let mut accepted = framed_generator.add_frames(channel, valid_frames);
+                    accepted.reverse();
+                    validations
+                        .into_iter()
+                        .map(|validation| match validation {
+                            Err(err) => FrameAcceptance::Rejected(err),
+                            Ok(()) if accepted.pop().unwrap_or(false) => FrameAcceptance::Accepted,
+                            Ok(()) => FrameAcceptance::Superseded,
+                        })
+                        .collect()
                 }
             }),
             on_frame_superseded_check: Box::new({
                 let frame_gen = Arc::clone(&self.time_queued_frame_generator);
+                let animation_gen = Arc::clone(&self.animation_frame_generator);
                 move |event| {
-                    frame_gen.is_frame_superseded(event.channel.unwrap_or(0), event.unix_micros)
+                    let channel = event.channel.unwrap_or(0);
+                    frame_gen.is_frame_superseded(channel, event.unix_micros)
+                        || animation_gen
+                            .active_channel(event.unix_micros)
+                            .is_some_and(|animation_channel| animation_channel > channel)
                 }
             }),
+            on_queue_status_check: Box::new({
+                let frame_gen = Arc::clone(&self.time_queued_frame_generator);
+                move |event| {
+                    let status = frame_gen.status(event.unix_micros);
+                    QueueStatus {
+                        live_channel: status.live_channel,
+                        channel_queue_depths: status.queued_frames,
+                        buffer_capacity: status.buffer_size,
+                        idle_seconds: status.idle_seconds,
+                    }
+                }
+            }),
+            on_queued_frames_check: Box::new({
+                let frame_gen = Arc::clone(&self.time_queued_frame_generator);
+                move |event| {
+                    frame_gen
+                        .pending_frames(event.channel)
+                        .into_iter()
+                        .map(|pending| QueuedFrame {
+                            channel: pending.channel,
+                            unix_micros: pending.unix_micros,
+                            width: pending.width,
+                            height: pending.height,
+                            is_patch: pending.is_patch,
+                        })
+                        .collect()
+                }
+            }),
+            on_queued_frames_removal: Box::new({
+                let frame_gen = Arc::clone(&self.time_queued_frame_generator);
+                move |event| {
+                    let from = event
+                        .from_unix_micros
+                        .map_or(Bound::Unbounded, Bound::Included);
+                    let until = event
+                        .until_unix_micros
+                        .map_or(Bound::Unbounded, Bound::Excluded);
+                    frame_gen.remove_frames(event.channel, (from, until))
+                }
+            }),
+            on_animation_received: Box::new({
+                let animation_gen = Arc::clone(&self.animation_frame_generator);
+                let gen_config = self.config.clone();
+                move |event| {
+                    let channel = event.channel.unwrap_or(0);
+                    let frames = event
+                        .frames
+                        .into_iter()
+                        .map(|animation_frame| (animation_frame.frame, animation_frame.duration))
+                        .collect();
+                    let animation = Animation::new(frames, event.loop_count)
+                        .map_err(FrameRejection::InvalidAnimation)?;
+                    for frame in animation.frames() {
+                        validate_frame(&gen_config, channel, frame, None)?;
+                    }
+
+                    animation_gen.play(channel, event.start_unix_micros, animation);
+                    Ok(())
+                }
+            }),
+            on_animation_cancelled: Box::new({
+                let animation_gen = Arc::clone(&self.animation_frame_generator);
+                move |event| animation_gen.cancel(event.channel.unwrap_or(0))
+            }),
         };
 
         let handle = task::spawn(async move {
@@ -71,9 +250,80 @@ impl WebQueriedFrameGenerator {
     }
 }
 
+fn validate_frame(
+    config: &WebQueriedFrameGeneratorConfig,
+    channel: i8,
+    frame: &Frame,
+    patch_position: Option<&Position>,
+) -> Result<(), FrameRejection> {
+    let (offset_x, offset_y) = patch_position.map_or((0, 0), |position| (position.x, position.y));
+    // Offsets come from clients, so the sums may overflow
+    if exceeds(offset_x, frame.width, config.display_width)
+        || exceeds(offset_y, frame.height, config.display_height)
+    {
+        return Err(FrameRejection::FrameTooLarge);
+    }
+    if let Placement::Absolute(position) = frame.placement() {
+        if exceeds(position.x, frame.width, config.display_width)
+            || exceeds(position.y, frame.height, config.display_height)
+        {
+            return Err(FrameRejection::PlacementOutOfBounds);
+        }
+    }
+
+    // Patches only cover part of a frame, so they aren't bound to the channel's frame sizes
+    let allowed_dimensions = config
+        .channels
+        .iter()
+        .find(|channel_info| channel_info.priority == channel)
+        .and_then(|channel_info| channel_info.allowed_dimensions.as_ref());
+    if let (Some(allowed_dimensions), None) = (allowed_dimensions, patch_position) {
+        if !allowed_dimensions.contains(&frame.dimensions()) {
+            return Err(FrameRejection::DimensionsNotAllowed {
+                width: frame.width,
+                height: frame.height,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn exceeds(start: u32, length: u32, limit: u32) -> bool {
+    start.checked_add(length).is_none_or(|end| end > limit)
+}
+
 impl FrameGenerator for WebQueriedFrameGenerator {
     fn generate(&self, unix_micros: u128) -> Option<Frame> {
-        self.time_queued_frame_generator.generate(unix_micros)
+        let queued_frame = self.time_queued_frame_generator.generate(unix_micros);
+        let live_channel = self.time_queued_frame_generator.live_channel(unix_micros);
+        let animation_channel = self.animation_frame_generator.active_channel(unix_micros);
+
+        // Frames sent explicitly take precedence over animations on the same channel
+        let (channel, frame) = match animation_channel {
+            Some(animation_channel)
+                if live_channel.is_none_or(|live_channel| live_channel < animation_channel) =>
+            {
+                let frame = self.animation_frame_generator.generate(unix_micros);
+                (Some(animation_channel), frame)
+            }
+            _ => (live_channel, queued_frame),
+        };
+
+        let mut displayed_channel = self.displayed_channel.lock().unwrap();
+        if *displayed_channel != channel {
+            *displayed_channel = channel;
+            self.events
+                .emit(StatusEvent::ActiveChannelChanged { channel });
+        }
+        frame
+    }
+
+    fn is_active(&self, unix_micros: u128) -> bool {
+        self.time_queued_frame_generator.is_active(unix_micros)
+            || self
+                .animation_frame_generator
+                .active_channel(unix_micros)
+                .is_some()
     }
 }
 
@@ -0,0 +1,69 @@
+use super::*;
+use crate::display::Pixel;
+
+fn config() -> WebQueriedFrameGeneratorConfig {
+    WebQueriedFrameGeneratorConfig {
+        channel_idle_seconds: 1.0,
+        display_width: 8,
+        display_height: 4,
+        display_fps: 20.0,
+        display_driver: "fake".to_string(),
+        channels: vec![],
+        compositing: false,
+    }
+}
+
+fn frame(width: u32, height: u32) -> Frame {
+    let pixels = vec![Pixel { r: 0, g: 0, b: 0 }; (width * height) as usize];
+    Frame::new(width, height, pixels).unwrap()
+}
+
+#[test]
+fn test_anchors_place_frame_within_display() {
+    let display = Dimensions {
+        width: 8,
+        height: 4,
+    };
+    let origin = |placement| frame(2, 2).with_placement(placement).origin_in(&display);
+
+    assert!(origin(Placement::Center) == Position { x: 3, y: 1 });
+    assert!(origin(Placement::TopLeft) == Position { x: 0, y: 0 });
+    assert!(origin(Placement::BottomRight) == Position { x: 6, y: 2 });
+    assert!(origin(Placement::Right) == Position { x: 6, y: 1 });
+    assert!(origin(Placement::Absolute(Position { x: 5, y: 1 })) == Position { x: 5, y: 1 });
+}
+
+#[test]
+fn test_absolute_offsets_must_stay_within_display() {
+    let config = config();
+    let placed = |x, y| frame(2, 2).with_placement(Placement::Absolute(Position { x, y }));
+
+    assert_eq!(validate_frame(&config, 0, &placed(6, 2), None), Ok(()));
+    assert_eq!(
+        validate_frame(&config, 0, &placed(7, 0), None),
+        Err(FrameRejection::PlacementOutOfBounds)
+    );
+    assert_eq!(
+        validate_frame(&config, 0, &placed(0, u32::MAX), None),
+        Err(FrameRejection::PlacementOutOfBounds)
+    );
+}
+
+#[test]
+fn test_patches_must_stay_within_display() {
+    let config = config();
+    let patch = |x, y| Position { x, y };
+
+    assert_eq!(
+        validate_frame(&config, 0, &frame(2, 2), Some(&patch(6, 2))),
+        Ok(())
+    );
+    assert_eq!(
+        validate_frame(&config, 0, &frame(2, 2), Some(&patch(0, 3))),
+        Err(FrameRejection::FrameTooLarge)
+    );
+    assert_eq!(
+        validate_frame(&config, 0, &frame(2, 2), Some(&patch(u32::MAX, 0))),
+        Err(FrameRejection::FrameTooLarge)
+    );
+}
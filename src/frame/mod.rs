@@ -1,4 +1,4 @@
-use crate::display::{Dimensions, Pixel};
+use crate::display::{Dimensions, Pixel, Position};
 use thiserror::Error;
 
 pub mod filler;
@@ -9,6 +9,24 @@ pub struct Frame {
     width: u32,
     height: u32,
     pixel_data: Vec<Pixel>,
+    // Opacity per pixel, frames without it are fully opaque
+    alpha_data: Option<Vec<u8>>,
+    placement: Placement,
+}
+
+#[derive(Clone, PartialEq, Eq, Default)]
+pub enum Placement {
+    #[default]
+    Center,
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+    Absolute(Position),
 }
 
 impl Frame {
@@ -21,9 +39,39 @@ impl Frame {
             width,
             height,
             pixel_data,
+            alpha_data: None,
+            placement: Placement::default(),
         })
     }
 
+    pub fn with_alpha(mut self, alpha_data: Vec<u8>) -> Result<Self, FrameError> {
+        if alpha_data.len() != self.pixel_data.len() {
+            return Err(FrameError::DimensionMismatch);
+        }
+
+        self.alpha_data = Some(alpha_data);
+        Ok(self)
+    }
+
+    /// Scales the opacity of every pixel, so the frame can be drawn translucently.
+    pub fn with_opacity(mut self, opacity: u8) -> Self {
+        if opacity == u8::MAX {
+            return self;
+        }
+
+        let scale = |alpha: u8| ((alpha as u16 * opacity as u16 + 127) / 255) as u8;
+        self.alpha_data = Some(match self.alpha_data {
+            Some(alpha_data) => alpha_data.into_iter().map(scale).collect(),
+            None => vec![opacity; self.pixel_data.len()],
+        });
+        self
+    }
+
+    pub fn with_placement(mut self, placement: Placement) -> Self {
+        self.placement = placement;
+        self
+    }
+
     pub fn dimensions(&self) -> Dimensions {
         Dimensions {
             width: self.width,
@@ -34,20 +82,145 @@ impl Frame {
     pub fn pixel_data(&self) -> &Vec<Pixel> {
         &self.pixel_data
     }
+
+    pub fn alpha_data(&self) -> Option<&Vec<u8>> {
+        self.alpha_data.as_ref()
+    }
+
+    pub fn placement(&self) -> &Placement {
+        &self.placement
+    }
+
+    /// Top left corner of the frame when placed within an area of the given dimensions.
+    pub fn origin_in(&self, dimensions: &Dimensions) -> Position {
+        let free_width = dimensions.width.saturating_sub(self.width);
+        let free_height = dimensions.height.saturating_sub(self.height);
+        let (x, y) = match &self.placement {
+            Placement::Center => (free_width / 2, free_height / 2),
+            Placement::TopLeft => (0, 0),
+            Placement::Top => (free_width / 2, 0),
+            Placement::TopRight => (free_width, 0),
+            Placement::Left => (0, free_height / 2),
+            Placement::Right => (free_width, free_height / 2),
+            Placement::BottomLeft => (0, free_height),
+            Placement::Bottom => (free_width / 2, free_height),
+            Placement::BottomRight => (free_width, free_height),
+            Placement::Absolute(position) => (
+                u32::min(position.x, free_width),
+                u32::min(position.y, free_height),
+            ),
+        };
+        Position { x, y }
+    }
     
     pub fn empty() -> Self {
         Self::new(0, 0, Vec::new()).unwrap()
     }
     
+    pub fn patched(&self, patch: &Frame, position: &Position) -> Self {
+        let mut pixel_data = self.pixel_data.clone();
+        let mut alpha_data = self.alpha_data.clone();
+        if position.x < self.width {
+            let visible_width = u32::min(patch.width, self.width - position.x) as usize;
+            let rows = (position.y..self.height).zip(0..patch.height);
+            for (y, patch_y) in rows {
+                let start = (y * self.width + position.x) as usize;
+                let patch_start = (patch_y * patch.width) as usize;
+                let Some(patch_alpha) = &patch.alpha_data else {
+                    pixel_data[start..start + visible_width].clone_from_slice(
+                        &patch.pixel_data[patch_start..patch_start + visible_width],
+                    );
+                    if let Some(alpha_data) = &mut alpha_data {
+                        alpha_data[start..start + visible_width].fill(u8::MAX);
+                    }
+                    continue;
+                };
+
+                // Transparent patch pixels let the patched frame show through
+                for offset in 0..visible_width {
+                    let (index, patch_index) = (start + offset, patch_start + offset);
+                    let alpha = patch_alpha[patch_index];
+                    match &mut alpha_data {
+                        Some(alpha_data) => {
+                            (pixel_data[index], alpha_data[index]) = composite(
+                                &patch.pixel_data[patch_index],
+                                alpha,
+                                &pixel_data[index],
+                                alpha_data[index],
+                            );
+                        }
+                        None => {
+                            pixel_data[index] =
+                                blend(&patch.pixel_data[patch_index], &pixel_data[index], alpha);
+                        }
+                    }
+                }
+            }
+        }
+
+        Self {
+            width: self.width,
+            height: self.height,
+            pixel_data,
+            alpha_data,
+            placement: self.placement.clone(),
+        }
+    }
+
     pub fn with_color(width: u32, height: u32, color: Pixel) -> Self {
         Self {
             width,
             height,
             pixel_data: vec![color; (width * height) as usize],
+            alpha_data: None,
+            placement: Placement::default(),
         }
     }
 }
 
+/// Blends `foreground` with the given opacity over `background`.
+pub fn blend(foreground: &Pixel, background: &Pixel, alpha: u8) -> Pixel {
+    let blend_channel = |foreground: u8, background: u8| {
+        let alpha = alpha as u16;
+        ((foreground as u16 * alpha + background as u16 * (255 - alpha) + 127) / 255) as u8
+    };
+    Pixel {
+        r: blend_channel(foreground.r, background.r),
+        g: blend_channel(foreground.g, background.g),
+        b: blend_channel(foreground.b, background.b),
+    }
+}
+
+/// Draws a translucent pixel over another translucent pixel, returning the color and opacity.
+pub fn composite(
+    foreground: &Pixel,
+    foreground_alpha: u8,
+    background: &Pixel,
+    background_alpha: u8,
+) -> (Pixel, u8) {
+    let (foreground_alpha, background_alpha) = (foreground_alpha as u32, background_alpha as u32);
+    // Weights are scaled by 255 to avoid rounding the background's share to zero
+    let foreground_weight = foreground_alpha * 255;
+    let background_weight = background_alpha * (255 - foreground_alpha);
+    let total_weight = foreground_weight + background_weight;
+    if total_weight == 0 {
+        return (background.clone(), 0);
+    }
+
+    let composite_channel = |foreground: u8, background: u8| {
+        ((foreground as u32 * foreground_weight
+            + background as u32 * background_weight
+            + total_weight / 2)
+            / total_weight) as u8
+    };
+    let pixel = Pixel {
+        r: composite_channel(foreground.r, background.r),
+        g: composite_channel(foreground.g, background.g),
+        b: composite_channel(foreground.b, background.b),
+    };
+    (pixel, ((total_weight + 127) / 255) as u8)
+}
+
 #[derive(Error, Debug)]
 pub enum FrameError {
     #[error("the amount of provided pixels does not correspond to the dimensions of the frame")]
@@ -8,12 +8,14 @@ use crate::startup::startup;
 mod config;
 mod context;
 mod display;
+mod event;
 mod frame;
+mod lib;
+mod metrics;
 mod run;
 mod shutdown;
 mod startup;
 mod web;
-mod lib;
 
 #[tokio::main]
 async fn main() {
@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(test)]
+mod tests;
+
+const DURATION_BUCKETS: [f64; 10] = [
+    0.0001, 0.00025, 0.0005, 0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1,
+];
+const PUSH_RATE_WINDOW: Duration = Duration::from_secs(1);
+
+type ChannelCounter = (&'static str, &'static str, fn(&ChannelFrameCounts) -> u64);
+
+#[derive(Clone, Copy)]
+pub enum FrameOutcome {
+    Accepted,
+    Superseded,
+    Rejected,
+}
+
+#[derive(Clone, Default)]
+pub struct Metrics {
+    state: Arc<MetricsState>,
+}
+
+#[derive(Default)]
+struct MetricsState {
+    channel_frames: Mutex<BTreeMap<i8, ChannelFrameCounts>>,
+    frames_pushed: AtomicU64,
+    recent_pushes: Mutex<VecDeque<Instant>>,
+    push_failures: AtomicU64,
+    fallback_activations: AtomicU64,
+    fill_duration: Mutex<Histogram>,
+    display_update_duration: Mutex<Histogram>,
+}
+
+#[derive(Clone, Copy, Default)]
+struct ChannelFrameCounts {
+    received: u64,
+    accepted: u64,
+    superseded: u64,
+    rejected: u64,
+    late: u64,
+}
+
+#[derive(Default)]
+struct Histogram {
+    bucket_counts: [u64; DURATION_BUCKETS.len()],
+    count: u64,
+    sum: f64,
+}
+
+impl Metrics {
+    pub fn record_frame(&self, channel: i8, outcome: FrameOutcome, is_late: bool) {
+        let mut channel_frames = self.state.channel_frames.lock().unwrap();
+        let counts = channel_frames.entry(channel).or_default();
+        counts.received += 1;
+        match outcome {
+            FrameOutcome::Accepted => counts.accepted += 1,
+            FrameOutcome::Superseded => counts.superseded += 1,
+            FrameOutcome::Rejected => counts.rejected += 1,
+        }
+        if is_late {
+            counts.late += 1;
+        }
+    }
+
+    pub fn record_frame_pushed(&self) {
+        self.state.frames_pushed.fetch_add(1, Ordering::Relaxed);
+        let now = Instant::now();
+        let mut recent_pushes = self.state.recent_pushes.lock().unwrap();
+        recent_pushes.push_back(now);
+        prune_pushes(&mut recent_pushes, now);
+    }
+
+    pub fn record_push_failure(&self) {
+        self.state.push_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_fallback_activation(&self) {
+        self.state
+            .fallback_activations
+            .fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn record_fill_duration(&self, duration: Duration) {
+        self.state.fill_duration.lock().unwrap().observe(duration);
+    }
+
+    pub fn record_display_update_duration(&self, duration: Duration) {
+        self.state
+            .display_update_duration
+            .lock()
+            .unwrap()
+            .observe(duration);
+    }
+
+    pub fn encode(&self, output: &mut String) {
+        let channel_frames = self.state.channel_frames.lock().unwrap().clone();
+        let channel_counters: [ChannelCounter; 5] = [
+            (
+                "rasgb_frames_received_total",
+                "Frames submitted to the web server",
+                |counts| counts.received,
+            ),
+            (
+                "rasgb_frames_accepted_total",
+                "Frames accepted into the queue",
+                |counts| counts.accepted,
+            ),
+            (
+                "rasgb_frames_superseded_total",
+                "Frames queued although a newer frame was already displayed",
+                |counts| counts.superseded,
+            ),
+            (
+                "rasgb_frames_rejected_total",
+                "Frames rejected as invalid or rate limited",
+                |counts| counts.rejected,
+            ),
+            (
+                "rasgb_frames_late_total",
+                "Frames received more than one display interval after their timestamp",
+                |counts| counts.late,
+            ),
+        ];
+        for (name, help, value) in channel_counters {
+            write_header(output, name, "counter", help);
+            for (channel, counts) in channel_frames.iter() {
+                write_channel_sample(output, name, *channel, value(counts));
+            }
+        }
+
+        let pushes_per_second = {
+            let mut recent_pushes = self.state.recent_pushes.lock().unwrap();
+            prune_pushes(&mut recent_pushes, Instant::now());
+            recent_pushes.len() as f64 / PUSH_RATE_WINDOW.as_secs_f64()
+        };
+        let counters = [
+            (
+                "rasgb_display_frames_pushed_total",
+                "Frames pushed to the display",
+                &self.state.frames_pushed,
+            ),
+            (
+                "rasgb_display_push_failures_total",
+                "Frames the display failed to show",
+                &self.state.push_failures,
+            ),
+            (
+                "rasgb_fallback_activations_total",
+                "Times the fallback frame generator took over",
+                &self.state.fallback_activations,
+            ),
+        ];
+        for (name, help, counter) in counters {
+            write_header(output, name, "counter", help);
+            let _ = writeln!(output, "{} {}", name, counter.load(Ordering::Relaxed));
+        }
+        write_header(
+            output,
+            "rasgb_display_frames_per_second",
+            "gauge",
+            "Frames pushed to the display during the last second",
+        );
+        let _ = writeln!(
+            output,
+            "rasgb_display_frames_per_second {}",
+            pushes_per_second
+        );
+
+        self.state.fill_duration.lock().unwrap().encode(
+            output,
+            "rasgb_filler_duration_seconds",
+            "Time spent letterboxing frames, excluding the display update",
+        );
+        self.state.display_update_duration.lock().unwrap().encode(
+            output,
+            "rasgb_display_update_duration_seconds",
+            "Time spent updating the display pixels",
+        );
+    }
+}
+
+fn prune_pushes(recent_pushes: &mut VecDeque<Instant>, now: Instant) {
+    while recent_pushes
+        .front()
+        .is_some_and(|pushed_at| now.duration_since(*pushed_at) > PUSH_RATE_WINDOW)
+    {
+        recent_pushes.pop_front();
+    }
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        if let Some(index) = DURATION_BUCKETS.iter().position(|bound| seconds <= *bound) {
+            self.bucket_counts[index] += 1;
+        }
+        self.count += 1;
+        self.sum += seconds;
+    }
+
+    fn encode(&self, output: &mut String, name: &str, help: &str) {
+        write_header(output, name, "histogram", help);
+        let mut cumulative_count = 0;
+        for (bound, count) in DURATION_BUCKETS.iter().zip(self.bucket_counts) {
+            cumulative_count += count;
+            let _ = writeln!(
+                output,
+                "{}_bucket{{le=\"{}\"}} {}",
+                name, bound, cumulative_count
+            );
+        }
+        let _ = writeln!(output, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(output, "{}_sum {}", name, self.sum);
+        let _ = writeln!(output, "{}_count {}", name, self.count);
+    }
+}
+
+pub fn write_header(output: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(output, "# HELP {} {}", name, help);
+    let _ = writeln!(output, "# TYPE {} {}", name, kind);
+}
+
+pub fn write_channel_sample(output: &mut String, name: &str, channel: i8, value: impl ToString) {
+    let _ = writeln!(
+        output,
+        "{}{{channel=\"{}\"}} {}",
+        name,
+        channel,
+        value.to_string()
+    );
+}
@@ -0,0 +1,36 @@
+use super::*;
+
+#[test]
+fn test_frame_counters_are_labelled_by_channel() {
+    let metrics = Metrics::default();
+    metrics.record_frame(0, FrameOutcome::Accepted, false);
+    metrics.record_frame(0, FrameOutcome::Rejected, true);
+    metrics.record_frame(3, FrameOutcome::Superseded, false);
+
+    let mut output = String::new();
+    metrics.encode(&mut output);
+
+    assert!(output.contains("rasgb_frames_received_total{channel=\"0\"} 2\n"));
+    assert!(output.contains("rasgb_frames_accepted_total{channel=\"0\"} 1\n"));
+    assert!(output.contains("rasgb_frames_rejected_total{channel=\"0\"} 1\n"));
+    assert!(output.contains("rasgb_frames_late_total{channel=\"0\"} 1\n"));
+    assert!(output.contains("rasgb_frames_superseded_total{channel=\"3\"} 1\n"));
+}
+
+#[test]
+fn test_histogram_buckets_are_cumulative() {
+    let metrics = Metrics::default();
+    metrics.record_display_update_duration(Duration::from_micros(80));
+    metrics.record_display_update_duration(Duration::from_millis(2));
+    metrics.record_display_update_duration(Duration::from_secs(1));
+
+    let mut output = String::new();
+    metrics.encode(&mut output);
+
+    let name = "rasgb_display_update_duration_seconds";
+    assert!(output.contains(&format!("{}_bucket{{le=\"0.0001\"}} 1\n", name)));
+    assert!(output.contains(&format!("{}_bucket{{le=\"0.0025\"}} 2\n", name)));
+    assert!(output.contains(&format!("{}_bucket{{le=\"0.1\"}} 2\n", name)));
+    assert!(output.contains(&format!("{}_bucket{{le=\"+Inf\"}} 3\n", name)));
+    assert!(output.contains(&format!("{}_count 3\n", name)));
+}
@@ -1,6 +1,9 @@
 mod signals;
 mod sync;
 
+#[cfg(test)]
+mod tests;
+
 use crate::context::RasGBContext;
 use crate::run::signals::exit_signal;
 use crate::run::sync::sync_frames;
@@ -18,6 +21,12 @@ pub async fn run(context: &RasGBContext) {
             _ = exit_signal() => break
         }
 
-        sync_frames(&context.display, &context.filler, &context.generator);
+        sync_frames(
+            &context.display,
+            &context.filler,
+            &context.generator,
+            &context.events,
+            &context.metrics,
+        );
     }
 }
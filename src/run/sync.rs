@@ -1,12 +1,16 @@
 use crate::display::Display;
+use crate::event::{EventBus, StatusEvent};
 use crate::frame::filler::FrameFiller;
 use crate::frame::gen::FrameGenerator;
+use crate::metrics::Metrics;
 use std::time::SystemTime;
 
 pub fn sync_frames(
     display: &impl Display,
     filler: &impl FrameFiller,
     frames: &impl FrameGenerator,
+    events: &EventBus,
+    metrics: &Metrics,
 ) {
     let current_time = SystemTime::now();
     let timestamp = current_time
@@ -18,8 +22,19 @@ pub fn sync_frames(
         if let Some(frame) = frame {
             if let Err(e) = filler.push_to_display(frame, display) {
                 eprintln!("failed to push frame: {}", e);
+                metrics.record_push_failure();
+                events.emit(StatusEvent::DisplayFailed {
+                    message: e.to_string(),
+                });
                 continue;
             }
+            metrics.record_frame_pushed();
+        } else if let Err(e) = display.refresh() {
+            eprintln!("failed to refresh display: {}", e);
+            metrics.record_push_failure();
+            events.emit(StatusEvent::DisplayFailed {
+                message: e.to_string(),
+            });
         }
         break;
     }
@@ -0,0 +1,30 @@
+use super::*;
+use crate::display::fake::FakeDisplay;
+use crate::display::{DisplayError, Pixel};
+use crate::event::{EventBus, StatusEvent};
+use crate::frame::filler::letterboxing::LetterboxingDisplayFiller;
+use crate::frame::gen::channel_time_queued::ChannelTimeQueuedFrameGenerator;
+use crate::frame::Frame;
+use crate::metrics::Metrics;
+
+#[test]
+fn test_display_failure_emitted() {
+    let black = Pixel { r: 0, g: 0, b: 0 };
+    let events = EventBus::default();
+    let mut receiver = events.subscribe();
+    let gen = ChannelTimeQueuedFrameGenerator::new(2, 1.0);
+    gen.add_frame(0, 0, Frame::with_color(4, 4, black.clone()));
+
+    sync_frames(
+        &FakeDisplay::new(2, 2),
+        &LetterboxingDisplayFiller::new(black),
+        &gen,
+        &events,
+        &Metrics::default(),
+    );
+
+    assert!(matches!(
+        receiver.try_recv(),
+        Ok(StatusEvent::DisplayFailed { message }) if message == DisplayError::FrameTooLarge.to_string()
+    ));
+}
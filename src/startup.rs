@@ -1,12 +1,22 @@
-use crate::config::{DisplayConfigDriver, RasGBConfig};
+use crate::config::{parse_cors_config, DisplayConfigDriver, RasGBConfig, RateLimitRuleConfig};
 use crate::context::RasGBContext;
+use crate::display::brightness::{BrightnessDisplay, MAX_BRIGHTNESS};
 use crate::display::fake::FakeDisplay;
-use crate::display::{Display, Pixel};
+use crate::display::output::{OutputDisplay, OutputState};
+use crate::display::snapshot::SnapshotDisplay;
+use crate::display::{Dimensions, Display, Pixel};
+use crate::event::EventBus;
 use crate::frame::filler::letterboxing::LetterboxingDisplayFiller;
 use crate::frame::gen::fallback::FallbackFrameGenerator;
 use crate::frame::gen::solid_color::SolidColorFrameGenerator;
 use crate::frame::gen::web::{WebQueriedFrameGenerator, WebQueriedFrameGeneratorConfig};
-use crate::web::WebServerConfig;
+use crate::metrics::Metrics;
+use crate::web;
+use crate::web::auth::AuthToken;
+use crate::web::rate_limit::RateLimit;
+use crate::web::{
+    BrightnessStatus, ChannelInfo, DisplayControl, WebServerConfig, WebServerTlsConfig,
+};
 use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::time::Duration;
@@ -15,22 +25,129 @@ use tokio_util::sync::CancellationToken;
 pub async fn startup(config: RasGBConfig) -> RasGBContext {
     let mut shutdown_token = CancellationToken::new();
 
-    let display: Box<dyn Display> = config.display.driver.to_display(&config);
+    let display = BrightnessDisplay::new(
+        config.display.driver.to_display(&config),
+        config.display.brightness.unwrap_or(MAX_BRIGHTNESS),
+    );
+    let brightness = display.brightness();
+    let display = SnapshotDisplay::new(display);
+    let snapshot = display.snapshot();
+    let display = OutputDisplay::new(display);
+    let output = display.control();
     let dimensions = display.dimensions();
-    let mut web_generator = WebQueriedFrameGenerator::new(WebQueriedFrameGeneratorConfig {
-        channel_idle_seconds: config.timing.idle_seconds.unwrap_or(1.0),
-        display_width: dimensions.width,
-        display_height: dimensions.height,
-        display_fps: config.display.fps,
-    });
+    let events = EventBus::default();
+    let metrics = Metrics::default();
+    let mut web_generator = WebQueriedFrameGenerator::new(
+        WebQueriedFrameGeneratorConfig {
+            channel_idle_seconds: config.timing.idle_seconds.unwrap_or(1.0),
+            display_width: dimensions.width,
+            display_height: dimensions.height,
+            display_fps: config.display.fps,
+            display_driver: config.display.driver.kind().to_string(),
+            channels: config
+                .channels
+                .iter()
+                .map(|(name, channel)| ChannelInfo {
+                    name: name.clone(),
+                    priority: channel.priority,
+                    idle_seconds: channel.idle_seconds,
+                    allowed_dimensions: channel.allowed_dimensions.as_ref().map(|dimensions| {
+                        dimensions
+                            .iter()
+                            .map(|dimensions| Dimensions {
+                                width: dimensions.width,
+                                height: dimensions.height,
+                            })
+                            .collect()
+                    }),
+                    description: channel.description.clone(),
+                    z_order: channel.z_order,
+                    opacity: channel.opacity,
+                })
+                .collect(),
+            compositing: config.compositing,
+        },
+        events.clone(),
+    )
+    .with_metrics(metrics.clone());
 
     let server_shutdown = shutdown_token.clone();
-    web_generator.start_server(WebServerConfig {
-        socket: SocketAddr::new(config.server.ip, config.server.port),
-        shutdown_signal: Some(Box::pin(async move {
-            server_shutdown.cancelled().await;
-        })),
-    });
+    web_generator.start_server(
+        WebServerConfig {
+            socket: SocketAddr::new(config.server.ip, config.server.port),
+            time_sync_socket: config
+                .server
+                .time_sync_port
+                .map(|port| SocketAddr::new(config.server.ip, port)),
+            shutdown_signal: Some(Box::pin(async move {
+                server_shutdown.cancelled().await;
+            })),
+            auth_tokens: config.auth.as_ref().map(|auth| {
+                auth.tokens
+                    .iter()
+                    .map(|token| AuthToken {
+                        token: token.token.clone(),
+                        channels: token
+                            .channels
+                            .as_ref()
+                            .map(|channels| channels.iter().copied().collect()),
+                        read_only: token.read_only,
+                    })
+                    .collect()
+            }),
+            tls: config.server.tls.as_ref().map(|tls| WebServerTlsConfig {
+                cert_path: tls.cert_path.clone(),
+                key_path: tls.key_path.clone(),
+            }),
+            channel_rate_limit: to_rate_limit(
+                config
+                    .rate_limit
+                    .as_ref()
+                    .and_then(|rate_limit| rate_limit.channel.as_ref()),
+            ),
+            client_rate_limit: to_rate_limit(
+                config
+                    .rate_limit
+                    .as_ref()
+                    .and_then(|rate_limit| rate_limit.client.as_ref()),
+            ),
+            cors: config.server.cors.as_ref().map(|cors| {
+                parse_cors_config(cors).expect("cors config is validated when loading the config")
+            }),
+        },
+        DisplayControl {
+            on_snapshot_request: Box::new(move || snapshot.pixels()),
+            on_brightness_check: Box::new({
+                let brightness = brightness.clone();
+                move || BrightnessStatus {
+                    level: brightness.level(),
+                    is_native: brightness.is_native(),
+                }
+            }),
+            on_brightness_change: Box::new(move |event| {
+                brightness.set_level(event.level);
+                BrightnessStatus {
+                    level: brightness.level(),
+                    is_native: brightness.is_native(),
+                }
+            }),
+            on_output_check: Box::new({
+                let output = output.clone();
+                move || match output.state() {
+                    OutputState::On => web::OutputState::On,
+                    OutputState::Blank => web::OutputState::Blank,
+                    OutputState::Frozen => web::OutputState::Frozen,
+                }
+            }),
+            on_output_change: Box::new(move |event| {
+                output.set_state(match event.state {
+                    web::OutputState::On => OutputState::On,
+                    web::OutputState::Blank => OutputState::Blank,
+                    web::OutputState::Frozen => OutputState::Frozen,
+                })
+            }),
+        },
+    );
 
     let generator = FallbackFrameGenerator::new(
         web_generator,
@@ -43,17 +160,30 @@ pub async fn startup(config: RasGBConfig) -> RasGBContext {
             config.timing.idle_seconds.unwrap_or(1.0),
             1.0 / config.display.fps,
         )),
-    );
+    )
+    .with_events(events.clone())
+    .with_metrics(metrics.clone());
 
     RasGBContext {
         config,
         generator: Box::new(generator),
-        display,
-        filler: LetterboxingDisplayFiller::new(Pixel { r: 0, g: 0, b: 0 }),
+        display: Box::new(display),
+        filler: LetterboxingDisplayFiller::new(Pixel { r: 0, g: 0, b: 0 })
+            .with_metrics(metrics.clone()),
+        events,
+        metrics,
         shutdown_token,
     }
 }
 
+fn to_rate_limit(rule: Option<&RateLimitRuleConfig>) -> RateLimit {
+    rule.map(|rule| RateLimit {
+        frames_per_second: rule.frames_per_second,
+        bytes_per_second: rule.bytes_per_second,
+    })
+    .unwrap_or_default()
+}
+
 impl DisplayConfigDriver {
     pub fn to_display(&self, config: &RasGBConfig) -> Box<dyn Display> {
         match self.clone() {
@@ -117,13 +247,20 @@ impl DisplayConfigDriver {
                     use crate::display::rgb_led_matrix::RgbLedMatrixDisplay;
                     use rpi_led_matrix::{LedMatrixOptions, LedRuntimeOptions};
 
-                    Box::new(RgbLedMatrixDisplay::from_options_gen(move || {
+                    // The matrix only takes its brightness on creation, from 1 to 100
+                    let brightness = config
+                        .display
+                        .brightness
+                        .unwrap_or(MAX_BRIGHTNESS)
+                        .clamp(1, MAX_BRIGHTNESS);
+                    let display = RgbLedMatrixDisplay::from_options_gen(move || {
                         let mut matrix_options = LedMatrixOptions::default();
                         let mut runtime_options = LedRuntimeOptions::default();
 
                         runtime_options.set_daemon(false);
                         runtime_options.set_drop_privileges(false);
                         matrix_options.set_refresh_rate(false);
+                        matrix_options.set_brightness(brightness).unwrap();
 
                         matrix_options.set_cols(panel_columns);
                         matrix_options.set_rows(panel_rows);
@@ -177,7 +314,8 @@ impl DisplayConfigDriver {
                         }
 
                         (Some(matrix_options), Some(runtime_options))
-                    }))
+                    });
+                    Box::new(display.with_brightness(brightness))
                 }
             }
         }
@@ -0,0 +1,15 @@
+use crate::web::api::frame::data::FrameData;
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct AnimationSubmitData {
+    pub start_unix_micros: Option<u128>,
+    pub loop_count: Option<u32>,
+    pub frames: Vec<AnimationFrameData>,
+}
+
+#[derive(Deserialize)]
+pub struct AnimationFrameData {
+    pub duration_millis: u64,
+    pub frame: FrameData,
+}
@@ -0,0 +1,107 @@
+mod data;
+
+use crate::web::api::animation::data::AnimationSubmitData;
+use crate::web::api::channel::resolve_channel;
+use crate::web::api::error::{ErrorCode, ResponseErrorExt, ResponseResult};
+use crate::web::api::frame::write::decode_placed_frame_data;
+use crate::web::rate_limit::ClientAddr;
+use crate::web::state::WebServerContext;
+use crate::web::{AnimationCancelledEvent, AnimationFrame, AnimationReceivedEvent};
+use anyhow::anyhow;
+use axum::extract::{ConnectInfo, Path, State};
+use axum::http::StatusCode;
+use axum::Json;
+use std::net::IpAddr;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+pub const MAX_ANIMATION_FRAMES: usize = 250;
+
+pub async fn put_animation_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path(channel): Path<String>,
+    Json(data): Json<AnimationSubmitData>,
+) -> ResponseResult<StatusCode> {
+    let channel = resolve_channel(&context, &channel)?;
+    play_animation(context, Some(channel), client.0.ip(), data).await
+}
+
+pub async fn put_animation(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Json(data): Json<AnimationSubmitData>,
+) -> ResponseResult<StatusCode> {
+    play_animation(context, None, client.0.ip(), data).await
+}
+
+pub async fn delete_animation_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    Path(channel): Path<String>,
+) -> ResponseResult<StatusCode> {
+    let channel = resolve_channel(&context, &channel)?;
+    cancel_animation(context, Some(channel)).await
+}
+
+pub async fn delete_animation(
+    State(context): State<Arc<WebServerContext>>,
+) -> ResponseResult<StatusCode> {
+    cancel_animation(context, None).await
+}
+
+async fn play_animation(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    client: IpAddr,
+    data: AnimationSubmitData,
+) -> ResponseResult<StatusCode> {
+    if data.frames.len() > MAX_ANIMATION_FRAMES {
+        return Err(anyhow!(
+            "animations may contain at most {} frames",
+            MAX_ANIMATION_FRAMES
+        )
+        .with_code(ErrorCode::PayloadTooLarge)
+        .with_context("max_frames", MAX_ANIMATION_FRAMES));
+    }
+
+    let start_unix_micros = match data.start_unix_micros {
+        Some(start_unix_micros) => context.clock_offsets.correct(client, start_unix_micros),
+        None => SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)?
+            .as_micros(),
+    };
+    let frames = data
+        .frames
+        .into_iter()
+        .map(|animation_frame| {
+            Ok(AnimationFrame {
+                frame: decode_placed_frame_data(animation_frame.frame)?,
+                duration: Duration::from_millis(animation_frame.duration_millis),
+            })
+        })
+        .collect::<ResponseResult<Vec<_>>>()?;
+
+    let event = AnimationReceivedEvent {
+        channel,
+        start_unix_micros,
+        frames,
+        loop_count: data.loop_count,
+    };
+    context.control.on_animation_received.deref()(event)?;
+
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn cancel_animation(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+) -> ResponseResult<StatusCode> {
+    let cancelled =
+        context.control.on_animation_cancelled.deref()(AnimationCancelledEvent { channel });
+    if !cancelled {
+        return Err(anyhow!("no animation playing on channel").with_code(ErrorCode::NotFound));
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
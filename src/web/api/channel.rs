@@ -0,0 +1,13 @@
+use crate::web::api::error::{ErrorCode, ResponseError, ResponseResult};
+use crate::web::state::WebServerContext;
+use anyhow::anyhow;
+
+pub fn resolve_channel(context: &WebServerContext, channel: &str) -> ResponseResult<i8> {
+    context.resolve_channel(channel).ok_or_else(|| {
+        ResponseError::new(
+            ErrorCode::UnknownChannel,
+            anyhow!("unknown channel `{}`", channel),
+        )
+        .with_context("channel", channel)
+    })
+}
@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct SnapshotQuery {
+    pub format: Option<SnapshotFormatData>,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotFormatData {
+    Png,
+    Raw,
+}
+
+#[derive(Deserialize)]
+pub struct BrightnessSubmitData {
+    pub brightness: u8,
+}
+
+#[derive(Serialize)]
+pub struct BrightnessData {
+    pub brightness: u8,
+    pub native: bool,
+}
+
+#[derive(Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputStateData {
+    #[serde(alias = "resume")]
+    On,
+    #[serde(alias = "off")]
+    Blank,
+    #[serde(alias = "freeze")]
+    Frozen,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct OutputData {
+    pub state: OutputStateData,
+}
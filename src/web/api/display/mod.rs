@@ -0,0 +1,140 @@
+mod data;
+
+use crate::display::brightness::MAX_BRIGHTNESS;
+use crate::display::Pixel;
+use crate::web::api::display::data::{
+    BrightnessData, BrightnessSubmitData, OutputData, OutputStateData, SnapshotFormatData,
+    SnapshotQuery,
+};
+use crate::web::api::error::{ErrorCode, ResponseErrorExt, ResponseResult};
+use crate::web::state::WebServerContext;
+use crate::web::{BrightnessChangeEvent, BrightnessStatus, OutputChangeEvent, OutputState};
+use anyhow::anyhow;
+use axum::body::Body;
+use axum::extract::{Query, State};
+use axum::http::header::{ACCEPT, CONTENT_TYPE};
+use axum::http::HeaderMap;
+use axum::response::Response;
+use axum::Json;
+use std::ops::Deref;
+use std::sync::Arc;
+
+pub async fn get_current_display(
+    State(context): State<Arc<WebServerContext>>,
+    Query(query): Query<SnapshotQuery>,
+    headers: HeaderMap,
+) -> ResponseResult<Response> {
+    let pixels = context.control.display.on_snapshot_request.deref()()
+        .ok_or(anyhow!("nothing has been displayed yet").with_code(ErrorCode::NotFound))?;
+
+    let format = query.format.unwrap_or_else(|| {
+        let accepts_raw = headers
+            .get(ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value.contains("application/octet-stream"));
+        if accepts_raw {
+            SnapshotFormatData::Raw
+        } else {
+            SnapshotFormatData::Png
+        }
+    });
+    let (content_type, bytes) = match format {
+        SnapshotFormatData::Png => ("image/png", encode_png(&context, &pixels)?),
+        SnapshotFormatData::Raw => ("application/octet-stream", pixels_to_rgb(&pixels)),
+    };
+
+    let response = Response::builder()
+        .header(CONTENT_TYPE, content_type)
+        .header("Frame-Width", context.control.display_width.to_string())
+        .header("Frame-Height", context.control.display_height.to_string())
+        .body(Body::from(bytes))?;
+    Ok(response)
+}
+
+pub async fn get_brightness(State(context): State<Arc<WebServerContext>>) -> Json<BrightnessData> {
+    let status = context.control.display.on_brightness_check.deref()();
+    Json(brightness_data(status))
+}
+
+pub async fn put_brightness(
+    State(context): State<Arc<WebServerContext>>,
+    Json(data): Json<BrightnessSubmitData>,
+) -> ResponseResult<Json<BrightnessData>> {
+    if data.brightness > MAX_BRIGHTNESS {
+        return Err(
+            anyhow!("brightness must be between 0 and {}", MAX_BRIGHTNESS)
+                .with_code(ErrorCode::InvalidRequest)
+                .with_context("max_brightness", MAX_BRIGHTNESS),
+        );
+    }
+    let status = context.control.display.on_brightness_change.deref()(BrightnessChangeEvent {
+        level: data.brightness,
+    });
+    Ok(Json(brightness_data(status)))
+}
+
+pub async fn get_output(State(context): State<Arc<WebServerContext>>) -> Json<OutputData> {
+    let state = context.control.display.on_output_check.deref()();
+    Json(OutputData {
+        state: match state {
+            OutputState::On => OutputStateData::On,
+            OutputState::Blank => OutputStateData::Blank,
+            OutputState::Frozen => OutputStateData::Frozen,
+        },
+    })
+}
+
+pub async fn put_output(
+    State(context): State<Arc<WebServerContext>>,
+    Json(data): Json<OutputData>,
+) -> Json<OutputData> {
+    let state = match data.state {
+        OutputStateData::On => OutputState::On,
+        OutputStateData::Blank => OutputState::Blank,
+        OutputStateData::Frozen => OutputState::Frozen,
+    };
+    context.control.display.on_output_change.deref()(OutputChangeEvent { state });
+    Json(data)
+}
+
+fn brightness_data(status: BrightnessStatus) -> BrightnessData {
+    BrightnessData {
+        brightness: status.level,
+        native: status.is_native,
+    }
+}
+
+#[cfg(feature = "images")]
+fn encode_png(context: &WebServerContext, pixels: &[Pixel]) -> ResponseResult<Vec<u8>> {
+    use image::{ImageFormat, RgbImage};
+    use std::io::Cursor;
+
+    let image = RgbImage::from_raw(
+        context.control.display_width,
+        context.control.display_height,
+        pixels_to_rgb(pixels),
+    )
+    .ok_or(anyhow!(
+        "display snapshot does not match the display dimensions"
+    ))?;
+
+    let mut bytes = Vec::new();
+    image.write_to(&mut Cursor::new(&mut bytes), ImageFormat::Png)?;
+    Ok(bytes)
+}
+
+#[cfg(not(feature = "images"))]
+fn encode_png(_context: &WebServerContext, _pixels: &[Pixel]) -> ResponseResult<Vec<u8>> {
+    Err(
+        anyhow!("feature `images` is not enabled but required for PNG snapshots")
+            .with_code(ErrorCode::FeatureDisabled)
+            .with_status(axum::http::StatusCode::NOT_ACCEPTABLE),
+    )
+}
+
+fn pixels_to_rgb(pixels: &[Pixel]) -> Vec<u8> {
+    pixels
+        .iter()
+        .flat_map(|pixel| [pixel.r, pixel.g, pixel.b])
+        .collect()
+}
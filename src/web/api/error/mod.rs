@@ -0,0 +1,306 @@
+use std::fmt::{Debug, Display, Formatter};
+
+use axum::http::header::{CONTENT_LENGTH, CONTENT_TYPE, RETRY_AFTER};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::display::DisplayError;
+use crate::frame::FrameError;
+use crate::web::api::frame::format::PixelFormatError;
+use crate::web::rate_limit::RateLimitExceeded;
+use crate::web::FrameRejection;
+
+#[cfg(test)]
+mod tests;
+
+pub type ResponseResult<T> = Result<T, ResponseError>;
+
+type ErrorContext = Vec<(&'static str, Value)>;
+
+/// Machine readable error codes, these are part of the API and must stay stable.
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InternalError,
+    InvalidRequest,
+    InvalidBody,
+    NotFound,
+    MethodNotAllowed,
+    UnsupportedMediaType,
+    PayloadTooLarge,
+    // Only used by builds without the `images` feature
+    #[cfg_attr(feature = "images", allow(dead_code))]
+    FeatureDisabled,
+    Unauthorized,
+    Forbidden,
+    RateLimited,
+    UnknownChannel,
+    InvalidBase64,
+    // Only used by builds with the `images` feature
+    #[cfg_attr(not(feature = "images"), allow(dead_code))]
+    InvalidImage,
+    InvalidPixelData,
+    MissingPalette,
+    InvalidPalette,
+    DimensionMismatch,
+    FrameTooLarge,
+    PlacementOutOfBounds,
+    DimensionsNotAllowed,
+    MissingPatchBase,
+    InvalidAnimation,
+}
+
+impl ErrorCode {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::InternalError => StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::InvalidRequest
+            | ErrorCode::MissingPalette
+            | ErrorCode::FrameTooLarge
+            | ErrorCode::PlacementOutOfBounds
+            | ErrorCode::DimensionsNotAllowed
+            | ErrorCode::MissingPatchBase
+            | ErrorCode::InvalidAnimation => StatusCode::BAD_REQUEST,
+            ErrorCode::InvalidBody
+            | ErrorCode::InvalidBase64
+            | ErrorCode::InvalidImage
+            | ErrorCode::InvalidPalette => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::NotFound | ErrorCode::UnknownChannel => StatusCode::NOT_FOUND,
+            ErrorCode::MethodNotAllowed => StatusCode::METHOD_NOT_ALLOWED,
+            ErrorCode::UnsupportedMediaType | ErrorCode::FeatureDisabled => {
+                StatusCode::UNSUPPORTED_MEDIA_TYPE
+            }
+            ErrorCode::PayloadTooLarge => StatusCode::PAYLOAD_TOO_LARGE,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::Forbidden => StatusCode::FORBIDDEN,
+            ErrorCode::RateLimited => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::InvalidPixelData | ErrorCode::DimensionMismatch => {
+                StatusCode::NOT_ACCEPTABLE
+            }
+        }
+    }
+
+    /// Code for responses that were not produced by a `ResponseError`, e.g. extractor rejections.
+    pub fn from_status(status: StatusCode) -> Self {
+        match status {
+            StatusCode::NOT_FOUND => ErrorCode::NotFound,
+            StatusCode::METHOD_NOT_ALLOWED => ErrorCode::MethodNotAllowed,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE => ErrorCode::UnsupportedMediaType,
+            StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+            StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::InvalidBody,
+            StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+            StatusCode::FORBIDDEN => ErrorCode::Forbidden,
+            StatusCode::TOO_MANY_REQUESTS => ErrorCode::RateLimited,
+            status if status.is_client_error() => ErrorCode::InvalidRequest,
+            _ => ErrorCode::InternalError,
+        }
+    }
+}
+
+pub struct ResponseError {
+    status: StatusCode,
+    code: ErrorCode,
+    error: anyhow::Error,
+    context: Map<String, Value>,
+}
+
+#[derive(Serialize)]
+struct ErrorData<'a> {
+    code: ErrorCode,
+    message: String,
+    #[serde(skip_serializing_if = "Map::is_empty")]
+    context: &'a Map<String, Value>,
+}
+
+impl ResponseError {
+    pub fn new(code: ErrorCode, error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            status: code.status(),
+            code,
+            error: error.into(),
+            context: Map::new(),
+        }
+    }
+
+    pub fn with_status(mut self, status: StatusCode) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_context(mut self, key: &str, value: impl Serialize) -> Self {
+        if let Ok(value) = serde_json::to_value(value) {
+            self.context.insert(key.to_string(), value);
+        }
+        self
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+}
+
+pub(crate) trait ResponseErrorExt {
+    fn with_code(self, code: ErrorCode) -> ResponseError;
+}
+
+impl Debug for ResponseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.error, f)
+    }
+}
+
+impl Display for ResponseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl IntoResponse for ResponseError {
+    fn into_response(self) -> Response {
+        if self.code == ErrorCode::InternalError {
+            eprintln!("request failed: {:?}", self.error);
+        }
+
+        let data = ErrorData {
+            code: self.code,
+            // The alternate format includes the context chain, e.g. which header was invalid
+            message: format!("{:#}", self.error),
+            context: &self.context,
+        };
+        let mut response = (self.status, Json(data)).into_response();
+        if let Some(exceeded) = self.error.downcast_ref::<RateLimitExceeded>() {
+            response
+                .headers_mut()
+                .insert(RETRY_AFTER, retry_after_seconds(exceeded).into());
+        }
+        response
+    }
+}
+
+// Retry-After only supports whole seconds
+fn retry_after_seconds(exceeded: &RateLimitExceeded) -> u64 {
+    exceeded.retry_after.as_secs_f64().ceil().max(1.0) as u64
+}
+
+impl<E: Into<anyhow::Error>> From<E> for ResponseError {
+    fn from(value: E) -> Self {
+        let error = value.into();
+        let (code, context) = classify(&error);
+        let mut response_error = Self::new(code, error);
+        for (key, value) in context {
+            response_error.context.insert(key.to_string(), value);
+        }
+        response_error
+    }
+}
+
+impl<E: Into<ResponseError>> ResponseErrorExt for E {
+    fn with_code(self, code: ErrorCode) -> ResponseError {
+        let mut error = self.into();
+        error.code = code;
+        error.status = code.status();
+        error
+    }
+}
+
+/// Maps known error types to their code and context, anything else is an internal error.
+fn classify(error: &anyhow::Error) -> (ErrorCode, ErrorContext) {
+    if let Some(exceeded) = error.downcast_ref::<RateLimitExceeded>() {
+        let retry_after = retry_after_seconds(exceeded);
+        (
+            ErrorCode::RateLimited,
+            vec![("retry_after_seconds", retry_after.into())],
+        )
+    } else if let Some(err) = error.downcast_ref::<FrameRejection>() {
+        frame_rejection_code(err)
+    } else if let Some(err) = error.downcast_ref::<PixelFormatError>() {
+        match err {
+            PixelFormatError::LengthMismatch {
+                format,
+                expected,
+                actual,
+            } => (
+                ErrorCode::InvalidPixelData,
+                vec![
+                    ("format", (*format).into()),
+                    ("expected_bytes", (*expected).into()),
+                    ("actual_bytes", (*actual).into()),
+                ],
+            ),
+            PixelFormatError::MissingPalette => (ErrorCode::MissingPalette, vec![]),
+            PixelFormatError::InvalidPalette => (ErrorCode::InvalidPalette, vec![]),
+            PixelFormatError::IndexOutOfRange(index) => {
+                (ErrorCode::InvalidPalette, vec![("index", (*index).into())])
+            }
+            PixelFormatError::Frame(err) => (frame_error_code(err), vec![]),
+        }
+    } else if let Some(err) = error.downcast_ref::<FrameError>() {
+        (frame_error_code(err), vec![])
+    } else if let Some(err) = error.downcast_ref::<DisplayError>() {
+        let code = match err {
+            DisplayError::DimensionMismatch => ErrorCode::DimensionMismatch,
+            DisplayError::FrameTooLarge => ErrorCode::FrameTooLarge,
+            DisplayError::Other(_) => ErrorCode::InternalError,
+        };
+        (code, vec![])
+    } else if error.downcast_ref::<base64::DecodeError>().is_some() {
+        (ErrorCode::InvalidBase64, vec![])
+    } else {
+        (ErrorCode::InternalError, vec![])
+    }
+}
+
+pub fn frame_rejection_code(rejection: &FrameRejection) -> (ErrorCode, ErrorContext) {
+    match rejection {
+        FrameRejection::FrameTooLarge => (ErrorCode::FrameTooLarge, vec![]),
+        FrameRejection::PlacementOutOfBounds => (ErrorCode::PlacementOutOfBounds, vec![]),
+        FrameRejection::DimensionsNotAllowed { width, height } => (
+            ErrorCode::DimensionsNotAllowed,
+            vec![("width", (*width).into()), ("height", (*height).into())],
+        ),
+        FrameRejection::MissingPatchBase => (ErrorCode::MissingPatchBase, vec![]),
+        FrameRejection::InvalidAnimation(_) => (ErrorCode::InvalidAnimation, vec![]),
+    }
+}
+
+fn frame_error_code(error: &FrameError) -> ErrorCode {
+    match error {
+        FrameError::DimensionMismatch => ErrorCode::DimensionMismatch,
+        FrameError::Other(_) => ErrorCode::InternalError,
+    }
+}
+
+/// Rewrites plain error responses, e.g. from extractors rejecting a request, into the JSON format.
+pub async fn json_error_response(response: Response) -> Response {
+    let status = response.status();
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/json"));
+    if !(status.is_client_error() || status.is_server_error()) || is_json {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let message = match axum::body::to_bytes(body, 64 * 1024).await {
+        Ok(bytes) if !bytes.is_empty() => String::from_utf8_lossy(&bytes).to_string(),
+        _ => status
+            .canonical_reason()
+            .unwrap_or("request failed")
+            .to_lowercase(),
+    };
+    let error = ResponseError::new(ErrorCode::from_status(status), anyhow::Error::msg(message))
+        .with_status(status);
+    let mut response = error.into_response();
+    // Keep headers like Allow or WWW-Authenticate, but describe the new body
+    for (name, value) in parts.headers.iter() {
+        if name != CONTENT_TYPE && name != CONTENT_LENGTH {
+            response.headers_mut().append(name, value.clone());
+        }
+    }
+    response
+}
@@ -0,0 +1,78 @@
+use super::*;
+use anyhow::anyhow;
+use axum::body::Body;
+use base64::Engine;
+
+async fn response_json(response: Response) -> Value {
+    let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+#[test]
+fn test_classifies_known_errors() {
+    let error = ResponseError::from(FrameError::DimensionMismatch);
+    assert_eq!(error.code(), ErrorCode::DimensionMismatch);
+    assert_eq!(error.status, StatusCode::NOT_ACCEPTABLE);
+
+    let decode_error = base64::engine::general_purpose::STANDARD
+        .decode("not base64!")
+        .unwrap_err();
+    let error = ResponseError::from(decode_error);
+    assert_eq!(error.code(), ErrorCode::InvalidBase64);
+    assert_eq!(error.status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let error = ResponseError::from(FrameRejection::FrameTooLarge);
+    assert_eq!(error.code(), ErrorCode::FrameTooLarge);
+    assert_eq!(error.status, StatusCode::BAD_REQUEST);
+
+    let error = ResponseError::from(anyhow!("something broke"));
+    assert_eq!(error.code(), ErrorCode::InternalError);
+    assert_eq!(error.status, StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[tokio::test]
+async fn test_responds_with_code_message_and_context() {
+    let error = ResponseError::from(FrameRejection::DimensionsNotAllowed {
+        width: 3,
+        height: 2,
+    });
+    let response = error.into_response();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+        response_json(response).await,
+        serde_json::json!({
+            "code": "dimensions_not_allowed",
+            "message": "frame dimensions 3x2 are not allowed on this channel",
+            "context": { "width": 3, "height": 2 },
+        })
+    );
+
+    let response = anyhow!("no frame")
+        .with_code(ErrorCode::NotFound)
+        .into_response();
+    assert_eq!(
+        response_json(response).await,
+        serde_json::json!({ "code": "not_found", "message": "no frame" })
+    );
+}
+
+#[tokio::test]
+async fn test_rewrites_plain_error_responses() {
+    let response = Response::builder()
+        .status(StatusCode::UNPROCESSABLE_ENTITY)
+        .header(CONTENT_TYPE, "text/plain")
+        .body(Body::from("missing field `width`"))
+        .unwrap();
+    let response = json_error_response(response).await;
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(
+        response_json(response).await,
+        serde_json::json!({ "code": "invalid_body", "message": "missing field `width`" })
+    );
+
+    let response = Response::new(Body::from("ok"));
+    let response = json_error_response(response).await;
+    assert!(response.headers().get(CONTENT_TYPE).is_none());
+}
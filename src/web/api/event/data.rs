@@ -0,0 +1,67 @@
+use crate::event::{FrameDropReason, StatusEvent};
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum StatusEventData {
+    ActiveChannelChanged {
+        channel: Option<i8>,
+    },
+    FrameDropped {
+        channel: i8,
+        unix_micros: u128,
+        reason: FrameDropReasonData,
+    },
+    FallbackActivated,
+    DisplayFailed {
+        message: String,
+    },
+    EventsMissed {
+        count: u64,
+    },
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameDropReasonData {
+    Evicted,
+    Superseded,
+    MissingPatchBase,
+}
+
+impl StatusEventData {
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatusEventData::ActiveChannelChanged { .. } => "active_channel_changed",
+            StatusEventData::FrameDropped { .. } => "frame_dropped",
+            StatusEventData::FallbackActivated => "fallback_activated",
+            StatusEventData::DisplayFailed { .. } => "display_failed",
+            StatusEventData::EventsMissed { .. } => "events_missed",
+        }
+    }
+}
+
+impl From<StatusEvent> for StatusEventData {
+    fn from(event: StatusEvent) -> Self {
+        match event {
+            StatusEvent::ActiveChannelChanged { channel } => {
+                StatusEventData::ActiveChannelChanged { channel }
+            }
+            StatusEvent::FrameDropped {
+                channel,
+                unix_micros,
+                reason,
+            } => StatusEventData::FrameDropped {
+                channel,
+                unix_micros,
+                reason: match reason {
+                    FrameDropReason::Evicted => FrameDropReasonData::Evicted,
+                    FrameDropReason::Superseded => FrameDropReasonData::Superseded,
+                    FrameDropReason::MissingPatchBase => FrameDropReasonData::MissingPatchBase,
+                },
+            },
+            StatusEvent::FallbackActivated => StatusEventData::FallbackActivated,
+            StatusEvent::DisplayFailed { message } => StatusEventData::DisplayFailed { message },
+        }
+    }
+}
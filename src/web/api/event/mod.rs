@@ -0,0 +1,38 @@
+mod data;
+
+use crate::web::api::event::data::StatusEventData;
+use crate::web::state::WebServerContext;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive};
+use axum::response::Sse;
+use futures_util::stream::{self, Stream, StreamExt};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+
+#[cfg(test)]
+mod tests;
+
+pub async fn get_events(
+    State(context): State<Arc<WebServerContext>>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let receiver = context.control.events.subscribe();
+    let events = stream::unfold(receiver, |mut receiver| async move {
+        let data = match receiver.recv().await {
+            Ok(event) => StatusEventData::from(event),
+            Err(RecvError::Lagged(count)) => StatusEventData::EventsMissed { count },
+            Err(RecvError::Closed) => return None,
+        };
+        Some((data, receiver))
+    })
+    .map(|data| {
+        let event = Event::default()
+            .event(data.name())
+            .json_data(&data)
+            .expect("status events are always serializable");
+        Ok(event)
+    })
+    .take_until(context.shutdown_token.clone().cancelled_owned());
+
+    Sse::new(events).keep_alive(KeepAlive::default())
+}
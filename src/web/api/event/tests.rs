@@ -0,0 +1,37 @@
+use super::*;
+use crate::event::{FrameDropReason, StatusEvent};
+use serde_json::json;
+
+#[test]
+fn test_status_events_named_and_tagged() {
+    let data = StatusEventData::from(StatusEvent::FrameDropped {
+        channel: 1,
+        unix_micros: 100,
+        reason: FrameDropReason::Evicted,
+    });
+    assert_eq!(data.name(), "frame_dropped");
+    assert_eq!(
+        serde_json::to_value(&data).unwrap(),
+        json!({ "type": "frame_dropped", "channel": 1, "unix_micros": 100, "reason": "evicted" })
+    );
+
+    let data = StatusEventData::from(StatusEvent::DisplayFailed {
+        message: "frame too large".to_string(),
+    });
+    assert_eq!(data.name(), "display_failed");
+    assert_eq!(
+        serde_json::to_value(&data).unwrap(),
+        json!({ "type": "display_failed", "message": "frame too large" })
+    );
+
+    let data = StatusEventData::from(StatusEvent::ActiveChannelChanged { channel: None });
+    assert_eq!(data.name(), "active_channel_changed");
+    assert_eq!(
+        StatusEventData::FallbackActivated.name(),
+        "fallback_activated"
+    );
+    assert_eq!(
+        StatusEventData::EventsMissed { count: 3 }.name(),
+        "events_missed"
+    );
+}
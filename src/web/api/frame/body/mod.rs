@@ -0,0 +1,92 @@
+use crate::web::api::error::{ErrorCode, ResponseError, ResponseErrorExt};
+use crate::web::api::frame::data::FrameSubmitData;
+use anyhow::anyhow;
+use axum::body::Bytes;
+use axum::extract::{FromRequest, Request};
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::Json;
+
+#[cfg(test)]
+mod tests;
+
+pub enum FrameSubmitBody {
+    Json(FrameSubmitData),
+    Raw(Bytes),
+    Image(Bytes, FrameImageFormat),
+}
+
+impl FrameSubmitBody {
+    pub fn payload_size(&self) -> usize {
+        match self {
+            FrameSubmitBody::Json(data) => data.frame.payload_size(),
+            FrameSubmitBody::Raw(bytes) | FrameSubmitBody::Image(bytes, _) => bytes.len(),
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum FrameImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Qoi,
+}
+
+impl<S: Send + Sync> FromRequest<S> for FrameSubmitBody {
+    type Rejection = ResponseError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let content_type = request
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_ascii_lowercase())
+            .unwrap_or_default();
+
+        match content_type.as_str() {
+            "application/json" => {
+                let Json(data) = Json::<FrameSubmitData>::from_request(request, state)
+                    .await
+                    .map_err(|rejection| {
+                        body_rejection(rejection.status(), rejection.body_text())
+                    })?;
+                Ok(FrameSubmitBody::Json(data))
+            }
+            "application/octet-stream" => {
+                let bytes = Bytes::from_request(request, state)
+                    .await
+                    .map_err(|rejection| {
+                        body_rejection(rejection.status(), rejection.body_text())
+                    })?;
+                Ok(FrameSubmitBody::Raw(bytes))
+            }
+            "image/png" | "image/jpeg" | "image/gif" | "image/qoi" | "image/x-qoi" => {
+                let format = match content_type.as_str() {
+                    "image/png" => FrameImageFormat::Png,
+                    "image/jpeg" => FrameImageFormat::Jpeg,
+                    "image/gif" => FrameImageFormat::Gif,
+                    _ => FrameImageFormat::Qoi,
+                };
+                let bytes = Bytes::from_request(request, state)
+                    .await
+                    .map_err(|rejection| {
+                        body_rejection(rejection.status(), rejection.body_text())
+                    })?;
+                Ok(FrameSubmitBody::Image(bytes, format))
+            }
+            _ => Err(anyhow!("unsupported content type `{}`", content_type)
+                .with_code(ErrorCode::UnsupportedMediaType)
+                .with_context("content_type", content_type)),
+        }
+    }
+}
+
+fn body_rejection(status: StatusCode, message: String) -> ResponseError {
+    let code = match status {
+        StatusCode::PAYLOAD_TOO_LARGE => ErrorCode::PayloadTooLarge,
+        _ => ErrorCode::InvalidBody,
+    };
+    ResponseError::new(code, anyhow!(message)).with_status(status)
+}
@@ -0,0 +1,64 @@
+use super::*;
+use axum::body::Body;
+use axum::response::IntoResponse;
+
+async fn extract(
+    content_type: Option<&str>,
+    body: &'static str,
+) -> Result<FrameSubmitBody, ResponseError> {
+    let mut request = Request::builder();
+    if let Some(content_type) = content_type {
+        request = request.header(CONTENT_TYPE, content_type);
+    }
+    FrameSubmitBody::from_request(request.body(Body::from(body)).unwrap(), &()).await
+}
+
+#[tokio::test]
+async fn test_dispatches_on_content_type() {
+    let body = r#"{"frame":{"width":1,"height":1,"pixels_b64":"AAAA"}}"#;
+    let result = extract(Some("application/json; charset=utf-8"), body).await;
+    assert!(matches!(result, Ok(FrameSubmitBody::Json(data)) if data.frame.width == 1));
+
+    let result = extract(Some("application/octet-stream"), "abc").await;
+    assert!(matches!(result, Ok(FrameSubmitBody::Raw(bytes)) if bytes == "abc"));
+
+    let result = extract(Some("Image/PNG"), "abc").await;
+    assert!(matches!(
+        result,
+        Ok(FrameSubmitBody::Image(_, FrameImageFormat::Png))
+    ));
+    let result = extract(Some("image/x-qoi"), "abc").await;
+    assert!(matches!(
+        result,
+        Ok(FrameSubmitBody::Image(_, FrameImageFormat::Qoi))
+    ));
+}
+
+#[tokio::test]
+async fn test_rejects_unsupported_content_types_and_invalid_json() {
+    for content_type in [Some("text/plain"), None] {
+        let Err(err) = extract(content_type, "abc").await else {
+            panic!("{:?} accepted", content_type);
+        };
+        assert_eq!(err.code(), ErrorCode::UnsupportedMediaType);
+        assert_eq!(
+            err.into_response().status(),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+    }
+
+    let Err(err) = extract(Some("application/json"), r#"{"frame":{}}"#).await else {
+        panic!("invalid frame accepted");
+    };
+    assert_eq!(err.code(), ErrorCode::InvalidBody);
+    assert_eq!(
+        err.into_response().status(),
+        StatusCode::UNPROCESSABLE_ENTITY
+    );
+
+    let Err(err) = extract(Some("application/json"), "{").await else {
+        panic!("malformed json accepted");
+    };
+    assert_eq!(err.code(), ErrorCode::InvalidBody);
+    assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+}
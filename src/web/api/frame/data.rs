@@ -1,8 +1,10 @@
-use serde::Deserialize;
+use crate::web::api::error::ErrorCode;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
 pub struct FrameSubmitData {
     pub frame: FrameData,
+    pub patch: Option<PositionData>,
 }
 
 #[derive(Deserialize)]
@@ -10,4 +12,96 @@ pub struct FrameData {
     pub width: u32,
     pub height: u32,
     pub pixels_b64: String,
+    #[serde(default)]
+    pub format: PixelFormatData,
+    pub palette_b64: Option<String>,
+    pub anchor: Option<AnchorData>,
+    pub offset: Option<PositionData>,
+}
+
+impl FrameData {
+    pub fn payload_size(&self) -> usize {
+        self.pixels_b64.len() + self.palette_b64.as_ref().map_or(0, String::len)
+    }
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum PixelFormatData {
+    #[default]
+    Rgb,
+    Rgba,
+    Rgb565,
+    Gray8,
+    Indexed,
+}
+
+#[derive(Deserialize, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorData {
+    Center,
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+#[derive(Deserialize)]
+pub struct PositionData {
+    pub x: u32,
+    pub y: u32,
+}
+
+#[derive(Deserialize)]
+pub struct FrameBatchSubmitData {
+    pub start_unix_micros: Option<u128>,
+    pub fps: Option<f64>,
+    pub frames: Vec<BatchFrameData>,
+}
+
+#[derive(Deserialize)]
+pub struct BatchFrameData {
+    pub unix_micros: Option<u128>,
+    pub frame: FrameData,
+}
+
+#[derive(Serialize)]
+pub struct FrameBatchResultData {
+    pub accepted: usize,
+    pub frames: Vec<FrameResultData>,
+}
+
+#[derive(Serialize)]
+pub struct FrameResultData {
+    pub unix_micros: Option<u128>,
+    pub status: FrameResultStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<ErrorCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FrameResultStatus {
+    Accepted,
+    Superseded,
+    Rejected,
+}
+
+#[derive(Deserialize)]
+pub struct FrameQuery {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub patch_x: Option<u32>,
+    pub patch_y: Option<u32>,
+    pub anchor: Option<AnchorData>,
+    pub offset_x: Option<u32>,
+    pub offset_y: Option<u32>,
+    pub format: Option<PixelFormatData>,
+    pub palette_b64: Option<String>,
 }
@@ -0,0 +1,141 @@
+use crate::display::Pixel;
+use crate::frame::{Frame, FrameError};
+use crate::web::api::frame::data::PixelFormatData;
+use thiserror::Error;
+
+#[cfg(test)]
+mod tests;
+
+pub const MAX_PALETTE_COLORS: usize = 256;
+
+#[derive(Error, Debug)]
+pub enum PixelFormatError {
+    #[error("expected {expected} bytes of `{format}` pixel data, got {actual}")]
+    LengthMismatch {
+        format: &'static str,
+        expected: usize,
+        actual: usize,
+    },
+    #[error("`indexed` frames require a palette")]
+    MissingPalette,
+    #[error("palettes must consist of RGB triples for 1 to {MAX_PALETTE_COLORS} colors")]
+    InvalidPalette,
+    #[error("palette index {0} is out of range")]
+    IndexOutOfRange(u8),
+    #[error(transparent)]
+    Frame(#[from] FrameError),
+}
+
+impl PixelFormatData {
+    pub fn name(&self) -> &'static str {
+        match self {
+            PixelFormatData::Rgb => "rgb",
+            PixelFormatData::Rgba => "rgba",
+            PixelFormatData::Rgb565 => "rgb565",
+            PixelFormatData::Gray8 => "gray8",
+            PixelFormatData::Indexed => "indexed",
+        }
+    }
+
+    pub fn bytes_per_pixel(&self) -> usize {
+        match self {
+            PixelFormatData::Rgb => 3,
+            PixelFormatData::Rgba => 4,
+            PixelFormatData::Rgb565 => 2,
+            PixelFormatData::Gray8 | PixelFormatData::Indexed => 1,
+        }
+    }
+}
+
+/// Converts pixel data in the given format into a frame, keeping the alpha of RGBA pixels.
+pub fn decode_pixels(
+    width: u32,
+    height: u32,
+    format: PixelFormatData,
+    palette: Option<&[u8]>,
+    bytes: &[u8],
+) -> Result<Frame, PixelFormatError> {
+    // Saturates instead of overflowing, no body can be long enough to match huge dimensions
+    let expected = (width as usize)
+        .saturating_mul(height as usize)
+        .saturating_mul(format.bytes_per_pixel());
+    if bytes.len() != expected {
+        return Err(PixelFormatError::LengthMismatch {
+            format: format.name(),
+            expected,
+            actual: bytes.len(),
+        });
+    }
+
+    let pixels = match format {
+        PixelFormatData::Rgb => rgb_to_pixels(bytes),
+        PixelFormatData::Rgba => {
+            let pixels = rgb_to_pixels_with_stride(bytes, 4);
+            let alpha_data = bytes.chunks_exact(4).map(|chunk| chunk[3]).collect();
+            return Ok(with_alpha(Frame::new(width, height, pixels)?, alpha_data)?);
+        }
+        PixelFormatData::Rgb565 => bytes
+            .chunks_exact(2)
+            .map(|chunk| {
+                let value = u16::from_le_bytes([chunk[0], chunk[1]]);
+                let (r, g, b) = (value >> 11, (value >> 5) & 0x3f, value & 0x1f);
+                Pixel {
+                    r: ((r << 3) | (r >> 2)) as u8,
+                    g: ((g << 2) | (g >> 4)) as u8,
+                    b: ((b << 3) | (b >> 2)) as u8,
+                }
+            })
+            .collect(),
+        PixelFormatData::Gray8 => bytes
+            .iter()
+            .map(|value| Pixel {
+                r: *value,
+                g: *value,
+                b: *value,
+            })
+            .collect(),
+        PixelFormatData::Indexed => {
+            let palette = decode_palette(palette.ok_or(PixelFormatError::MissingPalette)?)?;
+            bytes
+                .iter()
+                .map(|index| {
+                    palette
+                        .get(*index as usize)
+                        .cloned()
+                        .ok_or(PixelFormatError::IndexOutOfRange(*index))
+                })
+                .collect::<Result<_, _>>()?
+        }
+    };
+    Ok(Frame::new(width, height, pixels)?)
+}
+
+/// Attaches alpha data to a frame, leaving fully opaque frames without it.
+fn with_alpha(frame: Frame, alpha_data: Vec<u8>) -> Result<Frame, FrameError> {
+    if alpha_data.iter().all(|alpha| *alpha == u8::MAX) {
+        return Ok(frame);
+    }
+    frame.with_alpha(alpha_data)
+}
+
+fn decode_palette(bytes: &[u8]) -> Result<Vec<Pixel>, PixelFormatError> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(3) || bytes.len() / 3 > MAX_PALETTE_COLORS {
+        return Err(PixelFormatError::InvalidPalette);
+    }
+    Ok(rgb_to_pixels(bytes))
+}
+
+pub fn rgb_to_pixels(bytes: &[u8]) -> Vec<Pixel> {
+    rgb_to_pixels_with_stride(bytes, 3)
+}
+
+fn rgb_to_pixels_with_stride(bytes: &[u8], stride: usize) -> Vec<Pixel> {
+    bytes
+        .chunks_exact(stride)
+        .map(|chunk| Pixel {
+            r: chunk[0],
+            g: chunk[1],
+            b: chunk[2],
+        })
+        .collect()
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+const WHITE: Pixel = Pixel {
+    r: 255,
+    g: 255,
+    b: 255,
+};
+const RED: Pixel = Pixel { r: 255, g: 0, b: 0 };
+
+#[test]
+fn test_decodes_packed_formats() {
+    let frame = decode_pixels(
+        2,
+        1,
+        PixelFormatData::Rgb565,
+        None,
+        &[0x00, 0xf8, 0xff, 0xff],
+    )
+    .unwrap();
+    assert!(*frame.pixel_data() == vec![RED, WHITE]);
+
+    let frame = decode_pixels(2, 1, PixelFormatData::Gray8, None, &[0, 255]).unwrap();
+    assert!(*frame.pixel_data() == vec![Pixel { r: 0, g: 0, b: 0 }, WHITE]);
+    assert!(frame.alpha_data().is_none());
+
+    let result = decode_pixels(2, 2, PixelFormatData::Rgb565, None, &[0; 6]);
+    assert!(matches!(
+        result,
+        Err(PixelFormatError::LengthMismatch {
+            expected: 8,
+            actual: 6,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn test_rgba_keeps_alpha_of_translucent_frames() {
+    let frame = decode_pixels(
+        2,
+        1,
+        PixelFormatData::Rgba,
+        None,
+        &[255, 0, 0, 255, 255, 255, 255, 128],
+    )
+    .unwrap();
+    assert!(*frame.pixel_data() == vec![RED, WHITE]);
+    assert_eq!(frame.alpha_data(), Some(&vec![255, 128]));
+
+    let opaque = decode_pixels(1, 1, PixelFormatData::Rgba, None, &[255, 0, 0, 255]).unwrap();
+    assert!(opaque.alpha_data().is_none());
+}
+
+#[test]
+fn test_indexed_frames_look_up_palette() {
+    let palette = [255, 0, 0, 255, 255, 255];
+    let frame = decode_pixels(3, 1, PixelFormatData::Indexed, Some(&palette), &[1, 0, 1]).unwrap();
+    assert!(*frame.pixel_data() == vec![WHITE, RED, WHITE]);
+
+    let result = decode_pixels(1, 1, PixelFormatData::Indexed, Some(&palette), &[2]);
+    assert!(matches!(result, Err(PixelFormatError::IndexOutOfRange(2))));
+    let result = decode_pixels(1, 1, PixelFormatData::Indexed, None, &[0]);
+    assert!(matches!(result, Err(PixelFormatError::MissingPalette)));
+    let result = decode_pixels(1, 1, PixelFormatData::Indexed, Some(&palette[..4]), &[0]);
+    assert!(matches!(result, Err(PixelFormatError::InvalidPalette)));
+}
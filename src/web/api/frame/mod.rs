@@ -1,16 +1,26 @@
-mod data;
+mod body;
+pub mod data;
+pub mod format;
 mod read;
-mod write;
+mod stream;
+pub mod write;
 
 use crate::display::Pixel;
 use crate::frame::Frame;
+use crate::web::api::channel::resolve_channel;
 use crate::web::api::error::{ResponseErrorExt, ResponseResult};
+use crate::web::api::frame::body::FrameSubmitBody;
 use crate::web::api::frame::read::check_superseded_frame;
-use crate::web::api::frame::write::enqueue_frame;
+use crate::web::api::frame::stream::stream_frames;
+use crate::web::api::frame::write::{enqueue_frame, enqueue_frames};
+use crate::web::api::timestamp::FrameTimestamp;
+use crate::web::rate_limit::ClientAddr;
 use crate::web::state::WebServerContext;
 use crate::web::FrameReceivedEvent;
 use anyhow::anyhow;
-use axum::extract::{Path, State};
+use axum::extract::ws::WebSocketUpgrade;
+use axum::extract::{ConnectInfo, Path, Query, State};
+use axum::http::HeaderMap;
 use axum::http::StatusCode;
 use axum::response::Response;
 use axum::Json;
@@ -18,32 +28,95 @@ use base64::{alphabet, Engine};
 use std::ops::Deref;
 use std::sync::Arc;
 
+pub const MAX_BATCH_FRAMES: usize = 250;
+
 pub async fn post_frame_with_channel(
     State(context): State<Arc<WebServerContext>>,
-    Path((unix_micros, channel)): Path<(u128, i8)>,
-    Json(data): Json<data::FrameSubmitData>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path((timestamp, channel)): Path<(FrameTimestamp, String)>,
+    Query(query): Query<data::FrameQuery>,
+    headers: HeaderMap,
+    body: FrameSubmitBody,
 ) -> ResponseResult<StatusCode> {
-    enqueue_frame(context, Some(channel), unix_micros, data).await
+    let channel = resolve_channel(&context, &channel)?;
+    let client = client.0.ip();
+    let unix_micros = timestamp.resolve(&context, client);
+    enqueue_frame(
+        context,
+        Some(channel),
+        client,
+        unix_micros,
+        body,
+        query,
+        headers,
+    )
+    .await
 }
 
 pub async fn post_frame(
     State(context): State<Arc<WebServerContext>>,
-    Path(unix_micros): Path<u128>,
-    Json(data): Json<data::FrameSubmitData>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path(timestamp): Path<FrameTimestamp>,
+    Query(query): Query<data::FrameQuery>,
+    headers: HeaderMap,
+    body: FrameSubmitBody,
 ) -> ResponseResult<StatusCode> {
-    enqueue_frame(context, None, unix_micros, data).await
+    let client = client.0.ip();
+    let unix_micros = timestamp.resolve(&context, client);
+    enqueue_frame(context, None, client, unix_micros, body, query, headers).await
+}
+
+pub async fn post_frames_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path(channel): Path<String>,
+    Json(data): Json<data::FrameBatchSubmitData>,
+) -> ResponseResult<(StatusCode, Json<data::FrameBatchResultData>)> {
+    let channel = resolve_channel(&context, &channel)?;
+    enqueue_frames(context, Some(channel), client.0.ip(), data).await
+}
+
+pub async fn post_frames(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Json(data): Json<data::FrameBatchSubmitData>,
+) -> ResponseResult<(StatusCode, Json<data::FrameBatchResultData>)> {
+    enqueue_frames(context, None, client.0.ip(), data).await
 }
 
 pub async fn head_frame_with_channel(
     State(context): State<Arc<WebServerContext>>,
-    Path((unix_micros, channel)): Path<(u128, i8)>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path((timestamp, channel)): Path<(FrameTimestamp, String)>,
 ) -> ResponseResult<Response> {
+    let channel = resolve_channel(&context, &channel)?;
+    let unix_micros = timestamp.resolve(&context, client.0.ip());
     check_superseded_frame(context, Some(channel), unix_micros).await
 }
 
 pub async fn head_frame(
     State(context): State<Arc<WebServerContext>>,
-    Path(unix_micros): Path<u128>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path(timestamp): Path<FrameTimestamp>,
 ) -> ResponseResult<Response> {
+    let unix_micros = timestamp.resolve(&context, client.0.ip());
     check_superseded_frame(context, None, unix_micros).await
 }
+
+pub async fn get_frame_stream_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Path(channel): Path<String>,
+    upgrade: WebSocketUpgrade,
+) -> ResponseResult<Response> {
+    let channel = resolve_channel(&context, &channel)?;
+    Ok(stream_frames(context, Some(channel), client.0.ip(), upgrade).await)
+}
+
+pub async fn get_frame_stream(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    stream_frames(context, None, client.0.ip(), upgrade).await
+}
@@ -0,0 +1,142 @@
+use crate::frame::{Frame, FrameError};
+use crate::web::api::error::{ErrorCode, ResponseError, ResponseErrorExt};
+use crate::web::api::frame::data::{FrameResultData, FrameResultStatus};
+use crate::web::api::frame::format::rgb_to_pixels;
+use crate::web::api::frame::write::record_frame_result;
+use crate::web::state::WebServerContext;
+use crate::web::{FrameAcceptance, FrameReceivedEvent};
+use anyhow::anyhow;
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::response::Response;
+use std::net::IpAddr;
+use std::ops::Deref;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+// Binary frame messages start with a little endian header of
+// `unix_micros: u64`, `width: u32` and `height: u32`, followed by RGB24 pixel data.
+const HEADER_SIZE: usize = 16;
+
+struct FrameHeader {
+    unix_micros: u128,
+    width: u32,
+    height: u32,
+}
+
+fn parse_header(bytes: &[u8]) -> Option<(FrameHeader, &[u8])> {
+    if bytes.len() < HEADER_SIZE {
+        return None;
+    }
+    let (header, pixel_bytes) = bytes.split_at(HEADER_SIZE);
+    let header = FrameHeader {
+        unix_micros: u64::from_le_bytes(header[0..8].try_into().unwrap()) as u128,
+        width: u32::from_le_bytes(header[8..12].try_into().unwrap()),
+        height: u32::from_le_bytes(header[12..16].try_into().unwrap()),
+    };
+    Some((header, pixel_bytes))
+}
+
+pub async fn stream_frames(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    client: IpAddr,
+    upgrade: WebSocketUpgrade,
+) -> Response {
+    let pixel_count = context.control.display_width * context.control.display_height;
+    upgrade
+        .max_message_size(HEADER_SIZE + (pixel_count * 3) as usize)
+        .on_upgrade(move |socket| handle_socket(context, channel, client, socket))
+}
+
+async fn handle_socket(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    client: IpAddr,
+    mut socket: WebSocket,
+) {
+    while let Some(Ok(message)) = socket.recv().await {
+        let response = match message {
+            Message::Binary(bytes) => {
+                let result = receive_frame(&context, channel, client, &bytes);
+                record_frame_result(&context, channel, result.unix_micros, &result.status);
+                result
+            }
+            Message::Close(_) => break,
+            Message::Text(_) => FrameResultData {
+                unix_micros: None,
+                status: FrameResultStatus::Rejected,
+                code: Some(ErrorCode::InvalidBody),
+                reason: Some("frames must be sent as binary messages".to_string()),
+            },
+            _ => continue,
+        };
+
+        let Ok(response) = serde_json::to_string(&response) else {
+            continue;
+        };
+        if socket.send(Message::Text(response.into())).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn receive_frame(
+    context: &WebServerContext,
+    channel: Option<i8>,
+    client: IpAddr,
+    bytes: &[u8],
+) -> FrameResultData {
+    let rejected = |unix_micros, err: ResponseError| FrameResultData {
+        unix_micros,
+        status: FrameResultStatus::Rejected,
+        code: Some(err.code()),
+        reason: Some(err.to_string()),
+    };
+
+    let Some((header, pixel_bytes)) = parse_header(bytes) else {
+        let err = anyhow!("message is shorter than the frame header");
+        return rejected(None, err.with_code(ErrorCode::InvalidBody));
+    };
+    let unix_micros = context.clock_offsets.correct(client, header.unix_micros);
+
+    if let Err(exceeded) =
+        context
+            .rate_limiter
+            .check(channel.unwrap_or(0), client, 1, pixel_bytes.len())
+    {
+        return rejected(Some(unix_micros), exceeded.into());
+    }
+
+    // Checked before decoding, since huge dimensions would overflow the frame's pixel count
+    let expected_len = (header.width as usize)
+        .checked_mul(header.height as usize)
+        .and_then(|pixel_count| pixel_count.checked_mul(3));
+    if expected_len != Some(pixel_bytes.len()) {
+        return rejected(Some(unix_micros), FrameError::DimensionMismatch.into());
+    }
+    let frame = match Frame::new(header.width, header.height, rgb_to_pixels(pixel_bytes)) {
+        Ok(frame) => frame,
+        Err(err) => return rejected(Some(unix_micros), err.into()),
+    };
+    let event = FrameReceivedEvent {
+        channel,
+        unix_micros,
+        frame,
+        patch_position: None,
+    };
+    let status = match context.control.on_frame_received.deref()(event) {
+        FrameAcceptance::Accepted => FrameResultStatus::Accepted,
+        FrameAcceptance::Superseded => FrameResultStatus::Superseded,
+        FrameAcceptance::Rejected(rejection) => {
+            return rejected(Some(unix_micros), rejection.into())
+        }
+    };
+    FrameResultData {
+        unix_micros: Some(unix_micros),
+        status,
+        code: None,
+        reason: None,
+    }
+}
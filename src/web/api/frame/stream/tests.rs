@@ -0,0 +1,59 @@
+use super::*;
+use crate::web::tests::test_context;
+use std::net::Ipv4Addr;
+
+const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+
+fn message(unix_micros: u64, width: u32, height: u32, pixel_bytes: &[u8]) -> Vec<u8> {
+    let mut message = Vec::new();
+    message.extend_from_slice(&unix_micros.to_le_bytes());
+    message.extend_from_slice(&width.to_le_bytes());
+    message.extend_from_slice(&height.to_le_bytes());
+    message.extend_from_slice(pixel_bytes);
+    message
+}
+
+#[test]
+fn test_parses_header() {
+    let message = message(1_234, 2, 1, &[7; 6]);
+    let (header, pixel_bytes) = parse_header(&message).unwrap();
+    assert_eq!(header.unix_micros, 1_234);
+    assert_eq!(header.width, 2);
+    assert_eq!(header.height, 1);
+    assert_eq!(pixel_bytes, &[7; 6]);
+
+    assert!(parse_header(&message[..HEADER_SIZE - 1]).is_none());
+}
+
+#[test]
+fn test_replies_with_frame_acceptance() {
+    let mut context = test_context();
+    // Only the queue decides whether a frame is superseded
+    context.control.on_frame_superseded_check = Box::new(|_| true);
+
+    let result = receive_frame(&context, Some(1), CLIENT, &message(1_234, 2, 1, &[0; 6]));
+    assert!(result.status == FrameResultStatus::Accepted);
+    assert_eq!(result.unix_micros, Some(1_234));
+    assert!(result.code.is_none());
+
+    context.control.on_frame_received = Box::new(|_| FrameAcceptance::Superseded);
+    let result = receive_frame(&context, Some(1), CLIENT, &message(1_234, 2, 1, &[0; 6]));
+    assert!(result.status == FrameResultStatus::Superseded);
+    assert_eq!(result.unix_micros, Some(1_234));
+}
+
+#[test]
+fn test_rejects_malformed_messages() {
+    let context = test_context();
+
+    let result = receive_frame(&context, None, CLIENT, &[0; HEADER_SIZE - 1]);
+    assert!(result.status == FrameResultStatus::Rejected);
+    assert_eq!(result.code, Some(ErrorCode::InvalidBody));
+    assert_eq!(result.unix_micros, None);
+
+    for (width, height) in [(2, 2), (u32::MAX, u32::MAX), (u32::MAX, 2)] {
+        let result = receive_frame(&context, None, CLIENT, &message(1, width, height, &[0; 6]));
+        assert!(result.status == FrameResultStatus::Rejected);
+        assert_eq!(result.code, Some(ErrorCode::DimensionMismatch));
+    }
+}
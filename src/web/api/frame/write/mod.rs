@@ -0,0 +1,491 @@
+use crate::display::Position;
+use crate::frame::{Frame, Placement};
+use crate::metrics::FrameOutcome;
+use crate::web::api::error::{
+    frame_rejection_code, ErrorCode, ResponseError, ResponseErrorExt, ResponseResult,
+};
+use crate::web::api::frame::body::{FrameImageFormat, FrameSubmitBody};
+use crate::web::api::frame::data::{
+    AnchorData, FrameBatchResultData, FrameBatchSubmitData, FrameData, FrameQuery, FrameResultData,
+    FrameResultStatus, FrameSubmitData, PixelFormatData,
+};
+use crate::web::api::frame::format::decode_pixels;
+use crate::web::api::frame::MAX_BATCH_FRAMES;
+use crate::web::state::WebServerContext;
+use crate::web::{FrameAcceptance, FrameReceivedEvent, FramesReceivedEvent, ReceivedFrame};
+use anyhow::anyhow;
+use axum::http::{HeaderMap, StatusCode};
+use axum::Json;
+use base64::engine::{DecodePaddingMode, GeneralPurposeConfig};
+use base64::{alphabet, Engine};
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::Deserialize;
+use std::net::IpAddr;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+#[cfg(test)]
+mod tests;
+
+pub async fn enqueue_frame(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    client: IpAddr,
+    unix_micros: u128,
+    body: FrameSubmitBody,
+    query: FrameQuery,
+    headers: HeaderMap,
+) -> ResponseResult<StatusCode> {
+    let result = submit_frame(&context, channel, client, unix_micros, body, query, headers);
+    let status = result.as_ref().unwrap_or(&FrameResultStatus::Rejected);
+    record_frame_result(&context, channel, Some(unix_micros), status);
+    // Superseded frames are still queued, so they're accepted as well
+    result.map(|_| StatusCode::ACCEPTED)
+}
+
+fn submit_frame(
+    context: &WebServerContext,
+    channel: Option<i8>,
+    client: IpAddr,
+    unix_micros: u128,
+    body: FrameSubmitBody,
+    query: FrameQuery,
+    headers: HeaderMap,
+) -> ResponseResult<FrameResultStatus> {
+    check_rate_limit(context, channel, client, 1, body.payload_size())?;
+    let patch_position = patch_position(&body, &query)?;
+    let placement = placement(&body, &query)?;
+    let frame = decode_frame(context, body, &query, &headers)?.with_placement(placement);
+
+    let event = FrameReceivedEvent {
+        channel,
+        unix_micros,
+        frame,
+        patch_position,
+    };
+    match context.control.on_frame_received.deref()(event) {
+        FrameAcceptance::Accepted => Ok(FrameResultStatus::Accepted),
+        FrameAcceptance::Superseded => Ok(FrameResultStatus::Superseded),
+        FrameAcceptance::Rejected(rejection) => Err(rejection.into()),
+    }
+}
+
+pub async fn enqueue_frames(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    client: IpAddr,
+    data: FrameBatchSubmitData,
+) -> ResponseResult<(StatusCode, Json<FrameBatchResultData>)> {
+    let frame_count = data.frames.len();
+    let result = submit_frames(&context, channel, client, data);
+    match &result {
+        Ok((_, Json(results))) => {
+            for frame in results.frames.iter() {
+                record_frame_result(&context, channel, frame.unix_micros, &frame.status);
+            }
+        }
+        Err(_) => {
+            for _ in 0..frame_count {
+                record_frame_result(&context, channel, None, &FrameResultStatus::Rejected);
+            }
+        }
+    }
+    result
+}
+
+fn submit_frames(
+    context: &WebServerContext,
+    channel: Option<i8>,
+    client: IpAddr,
+    data: FrameBatchSubmitData,
+) -> ResponseResult<(StatusCode, Json<FrameBatchResultData>)> {
+    if data.frames.len() > MAX_BATCH_FRAMES {
+        return Err(
+            anyhow!("batches may contain at most {} frames", MAX_BATCH_FRAMES)
+                .with_code(ErrorCode::PayloadTooLarge)
+                .with_context("max_frames", MAX_BATCH_FRAMES),
+        );
+    }
+    let payload_size = data
+        .frames
+        .iter()
+        .map(|batch_frame| batch_frame.frame.payload_size())
+        .sum();
+    check_rate_limit(context, channel, client, data.frames.len(), payload_size)?;
+    if let Some(fps) = data.fps {
+        if !(fps > 0.0 && fps.is_finite()) {
+            return Err(
+                anyhow!("fps must be a positive number").with_code(ErrorCode::InvalidRequest)
+            );
+        }
+    }
+
+    let mut results = Vec::with_capacity(data.frames.len());
+    let mut received_frames = Vec::with_capacity(data.frames.len());
+    for (index, batch_frame) in data.frames.into_iter().enumerate() {
+        let unix_micros = match (batch_frame.unix_micros, data.start_unix_micros, data.fps) {
+            (Some(unix_micros), _, _) => unix_micros,
+            (None, Some(start_unix_micros), Some(fps)) => {
+                let offset_micros = (index as f64 * 1_000_000.0 / fps) as u128;
+                start_unix_micros
+                    .checked_add(offset_micros)
+                    .ok_or_else(|| {
+                        anyhow!("frame {} is timed too far in the future", index)
+                            .with_code(ErrorCode::InvalidRequest)
+                            .with_context("index", index)
+                    })?
+            }
+            _ => {
+                return Err(anyhow!(
+                    "frame {} has no `unix_micros` and the batch has no `start_unix_micros` and `fps`",
+                    index
+                )
+                .with_code(ErrorCode::InvalidRequest)
+                .with_context("index", index));
+            }
+        };
+        let unix_micros = context.clock_offsets.correct(client, unix_micros);
+
+        match decode_placed_frame_data(batch_frame.frame) {
+            Ok(frame) => {
+                received_frames.push(ReceivedFrame { unix_micros, frame });
+                results.push(None);
+            }
+            Err(err) => results.push(Some(FrameResultData {
+                unix_micros: Some(unix_micros),
+                status: FrameResultStatus::Rejected,
+                code: Some(err.code()),
+                reason: Some(err.to_string()),
+            })),
+        }
+    }
+
+    let unix_micros = received_frames
+        .iter()
+        .map(|received| received.unix_micros)
+        .collect::<Vec<_>>();
+    let event = FramesReceivedEvent {
+        channel,
+        frames: received_frames,
+    };
+    let mut acceptances = context.control.on_frames_received.deref()(event)
+        .into_iter()
+        .zip(unix_micros);
+    let frames = results
+        .into_iter()
+        .map(|result| {
+            result.unwrap_or_else(|| {
+                let (acceptance, unix_micros) = acceptances.next().unwrap();
+                let (status, rejection) = match acceptance {
+                    FrameAcceptance::Accepted => (FrameResultStatus::Accepted, None),
+                    FrameAcceptance::Superseded => (FrameResultStatus::Superseded, None),
+                    FrameAcceptance::Rejected(rejection) => {
+                        (FrameResultStatus::Rejected, Some(rejection))
+                    }
+                };
+                FrameResultData {
+                    unix_micros: Some(unix_micros),
+                    status,
+                    code: rejection
+                        .as_ref()
+                        .map(|rejection| frame_rejection_code(rejection).0),
+                    reason: rejection.map(|rejection| rejection.to_string()),
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let accepted = frames
+        .iter()
+        .filter(|frame| frame.status == FrameResultStatus::Accepted)
+        .count();
+    Ok((
+        StatusCode::ACCEPTED,
+        Json(FrameBatchResultData { accepted, frames }),
+    ))
+}
+
+pub fn record_frame_result(
+    context: &WebServerContext,
+    channel: Option<i8>,
+    unix_micros: Option<u128>,
+    status: &FrameResultStatus,
+) {
+    let outcome = match status {
+        FrameResultStatus::Accepted => FrameOutcome::Accepted,
+        FrameResultStatus::Superseded => FrameOutcome::Superseded,
+        FrameResultStatus::Rejected => FrameOutcome::Rejected,
+    };
+    // Frames arriving after their display interval has passed can only be shown late, if at all
+    let is_late = unix_micros.is_some_and(|unix_micros| {
+        let interval_micros = (1_000_000.0 / context.control.display_fps) as u128;
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .is_ok_and(|now| unix_micros.saturating_add(interval_micros) < now.as_micros())
+    });
+    context
+        .control
+        .metrics
+        .record_frame(channel.unwrap_or(0), outcome, is_late);
+}
+
+pub fn check_rate_limit(
+    context: &WebServerContext,
+    channel: Option<i8>,
+    client: IpAddr,
+    frames: usize,
+    bytes: usize,
+) -> ResponseResult<()> {
+    context
+        .rate_limiter
+        .check(channel.unwrap_or(0), client, frames, bytes)
+        .map_err(ResponseError::from)
+}
+
+fn decode_frame(
+    context: &WebServerContext,
+    body: FrameSubmitBody,
+    query: &FrameQuery,
+    headers: &HeaderMap,
+) -> ResponseResult<Frame> {
+    match body {
+        FrameSubmitBody::Json(data) => decode_frame_data(data.frame),
+        FrameSubmitBody::Raw(bytes) => {
+            let width = frame_dimension(query.width, headers, "Frame-Width")?;
+            let height = frame_dimension(query.height, headers, "Frame-Height")?;
+            let format = frame_format(query.format, headers)?;
+            let palette = match query.palette_b64.as_deref() {
+                Some(palette_b64) => Some(decode_base64(palette_b64)?),
+                None => match headers.get("Frame-Palette") {
+                    Some(value) => Some(decode_base64(value.to_str().map_err(|err| {
+                        anyhow!(err)
+                            .context("invalid `Frame-Palette` header")
+                            .with_code(ErrorCode::InvalidRequest)
+                    })?)?),
+                    None => None,
+                },
+            };
+
+            Ok(decode_pixels(
+                width,
+                height,
+                format,
+                palette.as_deref(),
+                &bytes,
+            )?)
+        }
+        FrameSubmitBody::Image(bytes, format) => decode_image_frame(context, &bytes, format),
+    }
+}
+
+fn patch_position(body: &FrameSubmitBody, query: &FrameQuery) -> ResponseResult<Option<Position>> {
+    if let FrameSubmitBody::Json(FrameSubmitData {
+        patch: Some(patch), ..
+    }) = body
+    {
+        return Ok(Some(Position {
+            x: patch.x,
+            y: patch.y,
+        }));
+    }
+
+    match (query.patch_x, query.patch_y) {
+        (Some(x), Some(y)) => Ok(Some(Position { x, y })),
+        (None, None) => Ok(None),
+        _ => Err(
+            anyhow!("both `patch_x` and `patch_y` must be provided for patches")
+                .with_code(ErrorCode::InvalidRequest),
+        ),
+    }
+}
+
+fn placement(body: &FrameSubmitBody, query: &FrameQuery) -> ResponseResult<Placement> {
+    let (anchor, offset) = match body {
+        FrameSubmitBody::Json(data)
+            if data.frame.anchor.is_some() || data.frame.offset.is_some() =>
+        {
+            (
+                data.frame.anchor,
+                data.frame
+                    .offset
+                    .as_ref()
+                    .map(|offset| (offset.x, offset.y)),
+            )
+        }
+        _ => {
+            let offset = match (query.offset_x, query.offset_y) {
+                (Some(x), Some(y)) => Some((x, y)),
+                (None, None) => None,
+                _ => {
+                    return Err(anyhow!(
+                        "both `offset_x` and `offset_y` must be provided for offsets"
+                    )
+                    .with_code(ErrorCode::InvalidRequest))
+                }
+            };
+            (query.anchor, offset)
+        }
+    };
+
+    resolve_placement(anchor, offset)
+}
+
+fn resolve_placement(
+    anchor: Option<AnchorData>,
+    offset: Option<(u32, u32)>,
+) -> ResponseResult<Placement> {
+    let placement = match (anchor, offset) {
+        (Some(_), Some(_)) => {
+            return Err(anyhow!("frames can either be anchored or offset, not both")
+                .with_code(ErrorCode::InvalidRequest))
+        }
+        (None, Some((x, y))) => Placement::Absolute(Position { x, y }),
+        (Some(AnchorData::Center), None) | (None, None) => Placement::Center,
+        (Some(AnchorData::TopLeft), None) => Placement::TopLeft,
+        (Some(AnchorData::Top), None) => Placement::Top,
+        (Some(AnchorData::TopRight), None) => Placement::TopRight,
+        (Some(AnchorData::Left), None) => Placement::Left,
+        (Some(AnchorData::Right), None) => Placement::Right,
+        (Some(AnchorData::BottomLeft), None) => Placement::BottomLeft,
+        (Some(AnchorData::Bottom), None) => Placement::Bottom,
+        (Some(AnchorData::BottomRight), None) => Placement::BottomRight,
+    };
+    Ok(placement)
+}
+
+pub fn decode_placed_frame_data(data: FrameData) -> ResponseResult<Frame> {
+    let placement = resolve_placement(
+        data.anchor,
+        data.offset.as_ref().map(|offset| (offset.x, offset.y)),
+    )?;
+    Ok(decode_frame_data(data)?.with_placement(placement))
+}
+
+fn decode_frame_data(data: FrameData) -> ResponseResult<Frame> {
+    let pixel_bytes = decode_base64(&data.pixels_b64)?;
+    let palette = data.palette_b64.as_deref().map(decode_base64).transpose()?;
+
+    Ok(decode_pixels(
+        data.width,
+        data.height,
+        data.format,
+        palette.as_deref(),
+        &pixel_bytes,
+    )?)
+}
+
+fn decode_base64(value: &str) -> ResponseResult<Vec<u8>> {
+    let base64_engine = base64::engine::general_purpose::GeneralPurpose::new(
+        &alphabet::STANDARD,
+        GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent),
+    );
+    Ok(base64_engine.decode(value)?)
+}
+
+#[cfg(feature = "images")]
+fn decode_image_frame(
+    context: &WebServerContext,
+    bytes: &[u8],
+    format: FrameImageFormat,
+) -> ResponseResult<Frame> {
+    use crate::web::api::frame::format::rgb_to_pixels;
+    use image::error::ImageError;
+    use image::{ImageFormat, ImageReader, Limits};
+    use std::io::Cursor;
+
+    let image_format = match format {
+        FrameImageFormat::Png => ImageFormat::Png,
+        FrameImageFormat::Jpeg => ImageFormat::Jpeg,
+        FrameImageFormat::Gif => ImageFormat::Gif,
+        FrameImageFormat::Qoi => ImageFormat::Qoi,
+    };
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), image_format);
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(context.control.display_width);
+    limits.max_image_height = Some(context.control.display_height);
+    reader.limits(limits);
+
+    let image = reader.decode().map_err(|err| match err {
+        ImageError::Limits(_) => anyhow!("frame too large").with_code(ErrorCode::FrameTooLarge),
+        _ => err.with_code(ErrorCode::InvalidImage),
+    })?;
+
+    if !image.color().has_alpha() {
+        let image = image.to_rgb8();
+        return Ok(Frame::new(
+            image.width(),
+            image.height(),
+            rgb_to_pixels(image.as_raw()),
+        )?);
+    }
+    let image = image.to_rgba8();
+    Ok(decode_pixels(
+        image.width(),
+        image.height(),
+        PixelFormatData::Rgba,
+        None,
+        image.as_raw(),
+    )?)
+}
+
+#[cfg(not(feature = "images"))]
+fn decode_image_frame(
+    _context: &WebServerContext,
+    _bytes: &[u8],
+    _format: FrameImageFormat,
+) -> ResponseResult<Frame> {
+    Err(
+        anyhow!("feature `images` is not enabled but required for image uploads")
+            .with_code(ErrorCode::FeatureDisabled),
+    )
+}
+
+fn frame_format(
+    query_value: Option<PixelFormatData>,
+    headers: &HeaderMap,
+) -> ResponseResult<PixelFormatData> {
+    if let Some(format) = query_value {
+        return Ok(format);
+    }
+
+    let Some(header_value) = headers.get("Frame-Format") else {
+        return Ok(PixelFormatData::default());
+    };
+    header_value
+        .to_str()
+        .map_err(|err| anyhow!(err))
+        .and_then(|value| {
+            PixelFormatData::deserialize(StrDeserializer::<ValueError>::new(value))
+                .map_err(|err| anyhow!(err))
+        })
+        .map_err(|err| {
+            err.context("invalid `Frame-Format` header")
+                .with_code(ErrorCode::InvalidRequest)
+        })
+}
+
+fn frame_dimension(
+    query_value: Option<u32>,
+    headers: &HeaderMap,
+    header_name: &str,
+) -> ResponseResult<u32> {
+    if let Some(value) = query_value {
+        return Ok(value);
+    }
+
+    let header_value = headers.get(header_name).ok_or(
+        anyhow!("missing `{}` header or query parameter", header_name)
+            .with_code(ErrorCode::InvalidRequest)
+            .with_context("header", header_name),
+    )?;
+    header_value
+        .to_str()
+        .map_err(|err| anyhow!(err))
+        .and_then(|value| value.parse::<u32>().map_err(|err| anyhow!(err)))
+        .map_err(|err| {
+            err.context(format!("invalid `{}` header", header_name))
+                .with_code(ErrorCode::InvalidRequest)
+                .with_context("header", header_name)
+        })
+}
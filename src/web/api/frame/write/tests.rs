@@ -0,0 +1,93 @@
+use super::*;
+use crate::web::tests::test_context;
+use axum::body::Bytes;
+use axum::http::HeaderValue;
+use axum::response::IntoResponse;
+
+fn query(width: Option<u32>, height: Option<u32>) -> FrameQuery {
+    FrameQuery {
+        width,
+        height,
+        patch_x: None,
+        patch_y: None,
+        anchor: None,
+        offset_x: None,
+        offset_y: None,
+        format: None,
+        palette_b64: None,
+    }
+}
+
+fn dimension_headers(width: &'static str, height: &'static str) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("Frame-Width", HeaderValue::from_static(width));
+    headers.insert("Frame-Height", HeaderValue::from_static(height));
+    headers
+}
+
+#[test]
+fn test_frame_dimension_prefers_query() {
+    let headers = dimension_headers("3", "2");
+    assert_eq!(frame_dimension(None, &headers, "Frame-Width").unwrap(), 3);
+    assert_eq!(
+        frame_dimension(Some(5), &headers, "Frame-Width").unwrap(),
+        5
+    );
+    assert_eq!(
+        frame_dimension(Some(5), &HeaderMap::new(), "Frame-Height").unwrap(),
+        5
+    );
+}
+
+#[test]
+fn test_frame_dimension_rejects_missing_and_invalid_headers() {
+    let err = frame_dimension(None, &HeaderMap::new(), "Frame-Width").unwrap_err();
+    assert_eq!(err.code(), ErrorCode::InvalidRequest);
+    assert_eq!(err.into_response().status(), StatusCode::BAD_REQUEST);
+
+    for value in ["-1", "two", "4294967296"] {
+        let headers = dimension_headers(value, value);
+        let err = frame_dimension(None, &headers, "Frame-Height").unwrap_err();
+        assert_eq!(err.code(), ErrorCode::InvalidRequest, "{}", value);
+        assert!(err.to_string().contains("Frame-Height"));
+    }
+}
+
+#[test]
+fn test_decodes_raw_frames() {
+    let context = test_context();
+    let body = FrameSubmitBody::Raw(Bytes::from_static(&[255; 6]));
+    let frame = decode_frame(
+        &context,
+        body,
+        &query(None, None),
+        &dimension_headers("2", "1"),
+    );
+    let dimensions = frame.unwrap().dimensions();
+    assert_eq!((dimensions.width, dimensions.height), (2, 1));
+
+    let body = FrameSubmitBody::Raw(Bytes::from_static(&[255; 6]));
+    let frame = decode_frame(&context, body, &query(Some(1), Some(2)), &HeaderMap::new());
+    let dimensions = frame.unwrap().dimensions();
+    assert_eq!((dimensions.width, dimensions.height), (1, 2));
+
+    let body = FrameSubmitBody::Raw(Bytes::from_static(&[255; 6]));
+    let Err(err) = decode_frame(&context, body, &query(Some(1), None), &HeaderMap::new()) else {
+        panic!("frame without height decoded");
+    };
+    assert_eq!(err.code(), ErrorCode::InvalidRequest);
+}
+
+#[test]
+fn test_rejects_raw_frames_of_wrong_length() {
+    let context = test_context();
+    for (width, height) in [(2, 2), (u32::MAX, u32::MAX)] {
+        let body = FrameSubmitBody::Raw(Bytes::from_static(&[255; 6]));
+        let query = query(Some(width), Some(height));
+        let Err(err) = decode_frame(&context, body, &query, &HeaderMap::new()) else {
+            panic!("{}x{} frame decoded from 6 bytes", width, height);
+        };
+        assert_eq!(err.code(), ErrorCode::InvalidPixelData);
+        assert_eq!(err.into_response().status(), StatusCode::NOT_ACCEPTABLE);
+    }
+}
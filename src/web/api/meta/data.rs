@@ -1,8 +1,19 @@
 use serde::Serialize;
+use std::net::IpAddr;
 
 #[derive(Serialize)]
 pub struct MetaData {
+    pub server: ServerData,
     pub display: DisplayData,
+    pub queue: QueueData,
+    pub channels: Vec<ChannelConfigData>,
+    pub rate_limit: RateLimitData,
+}
+
+#[derive(Serialize)]
+pub struct ServerData {
+    pub version: String,
+    pub uptime_seconds: f64,
 }
 
 #[derive(Serialize)]
@@ -10,4 +21,56 @@ pub struct DisplayData {
     pub width: u32,
     pub height: u32,
     pub fps: f64,
+    pub driver: String,
+}
+
+#[derive(Serialize)]
+pub struct QueueData {
+    pub live_channel: Option<i8>,
+    pub idle_seconds: f64,
+    pub buffer_capacity: usize,
+    pub buffered_frames: usize,
+    pub channels: Vec<ChannelData>,
+}
+
+#[derive(Serialize)]
+pub struct ChannelConfigData {
+    pub name: String,
+    pub priority: i8,
+    pub idle_seconds: Option<f64>,
+    pub allowed_dimensions: Option<Vec<DimensionsData>>,
+    pub description: Option<String>,
+    pub z_order: Option<i32>,
+    pub opacity: Option<f64>,
+}
+
+#[derive(Serialize)]
+pub struct DimensionsData {
+    pub width: u32,
+    pub height: u32,
+}
+
+#[derive(Serialize)]
+pub struct ChannelData {
+    pub channel: i8,
+    pub queued_frames: usize,
+}
+
+#[derive(Serialize)]
+pub struct RateLimitData {
+    pub rejected_submissions: u64,
+    pub channels: Vec<ChannelRejectionData>,
+    pub clients: Vec<ClientRejectionData>,
+}
+
+#[derive(Serialize)]
+pub struct ChannelRejectionData {
+    pub channel: i8,
+    pub rejected_submissions: u64,
+}
+
+#[derive(Serialize)]
+pub struct ClientRejectionData {
+    pub ip: IpAddr,
+    pub rejected_submissions: u64,
 }
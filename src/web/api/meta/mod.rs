@@ -1,20 +1,93 @@
 mod data;
 
 use crate::web::api::error::ResponseResult;
-use crate::web::api::meta::data::{DisplayData, MetaData};
+use crate::web::api::meta::data::{
+    ChannelConfigData, ChannelData, ChannelRejectionData, ClientRejectionData, DimensionsData,
+    DisplayData, MetaData, QueueData, RateLimitData, ServerData,
+};
 use crate::web::state::WebServerContext;
+use crate::web::QueueStatusCheckEvent;
 use axum::extract::State;
 use axum::Json;
+use std::ops::Deref;
 use std::sync::Arc;
+use std::time::SystemTime;
 
 pub async fn get_meta(
     State(context): State<Arc<WebServerContext>>,
 ) -> ResponseResult<Json<MetaData>> {
+    let unix_micros = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_micros();
+    let rejections = context.rate_limiter.rejections();
+    let queue_status =
+        context.control.on_queue_status_check.deref()(QueueStatusCheckEvent { unix_micros });
+
     let meta_data = MetaData {
+        server: ServerData {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            uptime_seconds: context.started_at.elapsed().as_secs_f64(),
+        },
         display: DisplayData {
             width: context.control.display_width,
             height: context.control.display_height,
             fps: context.control.display_fps,
+            driver: context.control.display_driver.clone(),
+        },
+        queue: QueueData {
+            live_channel: queue_status.live_channel,
+            idle_seconds: queue_status.idle_seconds,
+            buffer_capacity: queue_status.buffer_capacity,
+            buffered_frames: queue_status.channel_queue_depths.values().sum(),
+            channels: queue_status
+                .channel_queue_depths
+                .into_iter()
+                .map(|(channel, queued_frames)| ChannelData {
+                    channel,
+                    queued_frames,
+                })
+                .collect(),
+        },
+        channels: context
+            .control
+            .channels
+            .iter()
+            .map(|channel_info| ChannelConfigData {
+                name: channel_info.name.clone(),
+                priority: channel_info.priority,
+                idle_seconds: channel_info.idle_seconds,
+                allowed_dimensions: channel_info.allowed_dimensions.as_ref().map(|dimensions| {
+                    dimensions
+                        .iter()
+                        .map(|dimensions| DimensionsData {
+                            width: dimensions.width,
+                            height: dimensions.height,
+                        })
+                        .collect()
+                }),
+                description: channel_info.description.clone(),
+                z_order: channel_info.z_order,
+                opacity: channel_info.opacity,
+            })
+            .collect(),
+        rate_limit: RateLimitData {
+            rejected_submissions: rejections.total,
+            channels: rejections
+                .channels
+                .into_iter()
+                .map(|(channel, rejected_submissions)| ChannelRejectionData {
+                    channel,
+                    rejected_submissions,
+                })
+                .collect(),
+            clients: rejections
+                .clients
+                .into_iter()
+                .map(|(ip, rejected_submissions)| ClientRejectionData {
+                    ip,
+                    rejected_submissions,
+                })
+                .collect(),
         },
     };
     Ok(Json(meta_data))
@@ -0,0 +1,52 @@
+use crate::metrics::{write_channel_sample, write_header};
+use crate::web::api::error::ResponseResult;
+use crate::web::state::WebServerContext;
+use crate::web::QueueStatusCheckEvent;
+use axum::extract::State;
+use axum::http::header::CONTENT_TYPE;
+use axum::response::IntoResponse;
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+pub async fn get_metrics(
+    State(context): State<Arc<WebServerContext>>,
+) -> ResponseResult<impl IntoResponse> {
+    let unix_micros = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_micros();
+    let queue_status =
+        context.control.on_queue_status_check.deref()(QueueStatusCheckEvent { unix_micros });
+
+    let mut output = String::new();
+    context.control.metrics.encode(&mut output);
+
+    write_header(
+        &mut output,
+        "rasgb_queue_depth",
+        "gauge",
+        "Frames waiting in the queue of each channel",
+    );
+    for (channel, queued_frames) in queue_status.channel_queue_depths.iter() {
+        write_channel_sample(&mut output, "rasgb_queue_depth", *channel, queued_frames);
+    }
+
+    write_header(
+        &mut output,
+        "rasgb_rate_limited_submissions_total",
+        "counter",
+        "Submissions rejected by the channel or client rate limits",
+    );
+    for (channel, rejected) in context.rate_limiter.rejections().channels {
+        write_channel_sample(
+            &mut output,
+            "rasgb_rate_limited_submissions_total",
+            channel,
+            rejected,
+        );
+    }
+
+    Ok(([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], output))
+}
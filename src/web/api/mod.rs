@@ -1,18 +1,35 @@
 use crate::web::state::WebServerContext;
 use axum::extract::DefaultBodyLimit;
-use axum::routing::{get, head, post};
+use axum::routing::{get, head, post, put};
 use axum::Router;
 use std::sync::Arc;
 
-mod error;
+mod animation;
+mod channel;
+mod display;
+pub mod error;
+mod event;
 mod frame;
 mod meta;
+mod metrics;
+mod panel;
+mod queue;
+mod time;
+mod timestamp;
 
 pub fn frames_router(context: &WebServerContext) -> Router<Arc<WebServerContext>> {
     let pixel_count = context.control.display_width * context.control.display_height;
-    let max_frame_size = (pixel_count * 5) as usize; // Account for base64 encoding
+    let max_frame_size = (pixel_count * 6) as usize; // Account for base64 encoded RGBA
+
+    let max_batch_size = (max_frame_size + 1024) * frame::MAX_BATCH_FRAMES;
 
     Router::new()
+        .route(
+            "/frames/channel/{channel_index}",
+            post(frame::post_frames_with_channel),
+        )
+        .route("/frames", post(frame::post_frames))
+        .layer(DefaultBodyLimit::max(max_batch_size))
         .route(
             "/frame/{unix_micros}/channel/{channel_index}",
             post(frame::post_frame_with_channel),
@@ -24,9 +41,78 @@ pub fn frames_router(context: &WebServerContext) -> Router<Arc<WebServerContext>
             head(frame::head_frame_with_channel),
         )
         .route("/frame/{unix_micros}", head(frame::head_frame))
+        .route(
+            "/frame/stream/channel/{channel_index}",
+            get(frame::get_frame_stream_with_channel),
+        )
+        .route("/frame/stream", get(frame::get_frame_stream))
         .layer(DefaultBodyLimit::max(1024))
 }
 
+pub fn animations_router(context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    let pixel_count = context.control.display_width * context.control.display_height;
+    let max_frame_size = (pixel_count * 6) as usize; // Account for base64 encoded RGBA
+
+    let max_animation_size = (max_frame_size + 1024) * animation::MAX_ANIMATION_FRAMES;
+
+    Router::new()
+        .route(
+            "/animation/channel/{channel_index}",
+            put(animation::put_animation_with_channel)
+                .delete(animation::delete_animation_with_channel),
+        )
+        .route(
+            "/animation",
+            put(animation::put_animation).delete(animation::delete_animation),
+        )
+        .layer(DefaultBodyLimit::max(max_animation_size))
+}
+
+pub fn display_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new()
+        .route("/display/current", get(display::get_current_display))
+        .route(
+            "/display/brightness",
+            get(display::get_brightness).put(display::put_brightness),
+        )
+        .route(
+            "/display/output",
+            get(display::get_output).put(display::put_output),
+        )
+}
+
+pub fn events_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new().route("/events", get(event::get_events))
+}
+
+pub fn queue_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new()
+        .route(
+            "/queue/channel/{channel_index}",
+            get(queue::get_queue_with_channel).delete(queue::delete_queue_with_channel),
+        )
+        .route("/queue", get(queue::get_queue).delete(queue::delete_queue))
+}
+
 pub fn meta_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
-    Router::new().route("/", get(meta::get_meta))
+    Router::new().route("/meta", get(meta::get_meta))
+}
+
+pub fn time_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new().route("/time", get(time::get_time)).route(
+        "/time/offset",
+        get(time::get_clock_offset)
+            .put(time::put_clock_offset)
+            .delete(time::delete_clock_offset),
+    )
+}
+
+pub fn metrics_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new().route("/metrics", get(metrics::get_metrics))
+}
+
+pub fn panel_router(_context: &WebServerContext) -> Router<Arc<WebServerContext>> {
+    Router::new()
+        .route("/", get(panel::get_root))
+        .route("/panel", get(panel::get_panel))
 }
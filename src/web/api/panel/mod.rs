@@ -0,0 +1,12 @@
+use axum::response::{Html, Redirect};
+
+// The page only talks to the HTTP API, so it works with any token it is given
+const PANEL_HTML: &str = include_str!("panel.html");
+
+pub async fn get_panel() -> Html<&'static str> {
+    Html(PANEL_HTML)
+}
+
+pub async fn get_root() -> Redirect {
+    Redirect::to("/panel")
+}
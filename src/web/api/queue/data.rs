@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+pub struct QueueData {
+    pub channels: Vec<QueueChannelData>,
+}
+
+#[derive(Serialize)]
+pub struct QueueChannelData {
+    pub channel: i8,
+    pub frames: Vec<QueuedFrameData>,
+}
+
+#[derive(Serialize)]
+pub struct QueuedFrameData {
+    pub unix_micros: u128,
+    pub width: u32,
+    pub height: u32,
+    pub patch: bool,
+}
+
+// Query strings can't carry `u128`, which is fine for any realistic timestamp
+#[derive(Deserialize)]
+pub struct QueueRemovalQuery {
+    pub from_unix_micros: Option<u64>,
+    pub until_unix_micros: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct QueueRemovalData {
+    pub removed: usize,
+}
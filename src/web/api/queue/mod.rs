@@ -0,0 +1,96 @@
+mod data;
+
+use crate::web::api::channel::resolve_channel;
+use crate::web::api::error::{ErrorCode, ResponseErrorExt, ResponseResult};
+use crate::web::api::queue::data::{
+    QueueChannelData, QueueData, QueueRemovalData, QueueRemovalQuery, QueuedFrameData,
+};
+use crate::web::state::WebServerContext;
+use crate::web::{QueuedFramesCheckEvent, QueuedFramesRemovalEvent};
+use anyhow::anyhow;
+use axum::extract::{Path, Query, State};
+use axum::Json;
+use std::ops::Deref;
+use std::sync::Arc;
+
+pub async fn get_queue_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    Path(channel): Path<String>,
+) -> ResponseResult<Json<QueueData>> {
+    let channel = resolve_channel(&context, &channel)?;
+    list_queue(context, Some(channel)).await
+}
+
+pub async fn get_queue(
+    State(context): State<Arc<WebServerContext>>,
+) -> ResponseResult<Json<QueueData>> {
+    list_queue(context, None).await
+}
+
+pub async fn delete_queue_with_channel(
+    State(context): State<Arc<WebServerContext>>,
+    Path(channel): Path<String>,
+    Query(query): Query<QueueRemovalQuery>,
+) -> ResponseResult<Json<QueueRemovalData>> {
+    let channel = resolve_channel(&context, &channel)?;
+    clear_queue(context, Some(channel), query).await
+}
+
+pub async fn delete_queue(
+    State(context): State<Arc<WebServerContext>>,
+    Query(query): Query<QueueRemovalQuery>,
+) -> ResponseResult<Json<QueueRemovalData>> {
+    clear_queue(context, None, query).await
+}
+
+async fn list_queue(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+) -> ResponseResult<Json<QueueData>> {
+    let mut queued_frames =
+        context.control.on_queued_frames_check.deref()(QueuedFramesCheckEvent { channel });
+    queued_frames.sort_by_key(|queued| (queued.channel, queued.unix_micros));
+
+    let mut channels: Vec<QueueChannelData> = Vec::new();
+    for queued in queued_frames {
+        let frame_data = QueuedFrameData {
+            unix_micros: queued.unix_micros,
+            width: queued.width,
+            height: queued.height,
+            patch: queued.is_patch,
+        };
+        match channels.last_mut() {
+            Some(channel_data) if channel_data.channel == queued.channel => {
+                channel_data.frames.push(frame_data)
+            }
+            _ => channels.push(QueueChannelData {
+                channel: queued.channel,
+                frames: vec![frame_data],
+            }),
+        }
+    }
+
+    Ok(Json(QueueData { channels }))
+}
+
+async fn clear_queue(
+    context: Arc<WebServerContext>,
+    channel: Option<i8>,
+    query: QueueRemovalQuery,
+) -> ResponseResult<Json<QueueRemovalData>> {
+    if let (Some(from), Some(until)) = (query.from_unix_micros, query.until_unix_micros) {
+        if from > until {
+            return Err(
+                anyhow!("`from_unix_micros` must not be after `until_unix_micros`")
+                    .with_code(ErrorCode::InvalidRequest),
+            );
+        }
+    }
+
+    let removed = context.control.on_queued_frames_removal.deref()(QueuedFramesRemovalEvent {
+        channel,
+        from_unix_micros: query.from_unix_micros.map(u128::from),
+        until_unix_micros: query.until_unix_micros.map(u128::from),
+    });
+    Ok(Json(QueueRemovalData { removed }))
+}
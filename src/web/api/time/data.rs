@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Deserialize)]
+pub struct TimeQuery {
+    pub client_unix_micros: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct TimeData {
+    pub client_unix_micros: Option<u64>,
+    pub received_unix_micros: u128,
+    pub sent_unix_micros: u128,
+}
+
+#[derive(Deserialize, Serialize)]
+pub struct ClockOffsetData {
+    pub offset_micros: i64,
+}
@@ -0,0 +1,56 @@
+mod data;
+
+use crate::web::api::error::{ErrorCode, ResponseErrorExt, ResponseResult};
+use crate::web::api::time::data::{ClockOffsetData, TimeData, TimeQuery};
+use crate::web::clock::unix_micros_now;
+use crate::web::rate_limit::ClientAddr;
+use crate::web::state::WebServerContext;
+use anyhow::anyhow;
+use axum::extract::{ConnectInfo, Query, State};
+use axum::http::StatusCode;
+use axum::Json;
+use std::sync::Arc;
+
+// Clients estimate their offset as `((received - client) + (sent - now)) / 2`
+// and the round trip time as `(now - client) - (sent - received)`
+pub async fn get_time(Query(query): Query<TimeQuery>) -> Json<TimeData> {
+    let received_unix_micros = unix_micros_now();
+    Json(TimeData {
+        client_unix_micros: query.client_unix_micros,
+        received_unix_micros,
+        sent_unix_micros: unix_micros_now(),
+    })
+}
+
+pub async fn get_clock_offset(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+) -> ResponseResult<Json<ClockOffsetData>> {
+    let offset_micros = context
+        .clock_offsets
+        .get(client.0.ip())
+        .ok_or(anyhow!("no clock offset registered").with_code(ErrorCode::NotFound))?;
+    Ok(Json(ClockOffsetData { offset_micros }))
+}
+
+pub async fn put_clock_offset(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+    Json(data): Json<ClockOffsetData>,
+) -> Json<ClockOffsetData> {
+    context
+        .clock_offsets
+        .register(client.0.ip(), data.offset_micros);
+    Json(data)
+}
+
+pub async fn delete_clock_offset(
+    State(context): State<Arc<WebServerContext>>,
+    ConnectInfo(client): ConnectInfo<ClientAddr>,
+) -> StatusCode {
+    if context.clock_offsets.remove(client.0.ip()) {
+        StatusCode::NO_CONTENT
+    } else {
+        StatusCode::NOT_FOUND
+    }
+}
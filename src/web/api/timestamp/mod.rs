@@ -0,0 +1,116 @@
+use crate::web::clock::unix_micros_now;
+use crate::web::state::WebServerContext;
+use serde::{Deserialize, Deserializer};
+use std::net::IpAddr;
+use std::str::FromStr;
+use time::format_description::well_known::Rfc3339;
+use time::OffsetDateTime;
+
+#[cfg(test)]
+mod tests;
+
+// Keeps offsets far away from overflowing when added to the current time
+const MAX_OFFSET_MICROS: i128 = i64::MAX as i128;
+
+/// A frame time as given in a route, either absolute or relative to the server clock.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FrameTimestamp {
+    UnixMicros(u128),
+    Now { offset_micros: i128 },
+    NextTick,
+}
+
+impl FrameTimestamp {
+    /// Absolute timestamps come from the client's clock and are corrected by its registered offset.
+    pub fn resolve(self, context: &WebServerContext, client: IpAddr) -> u128 {
+        match self {
+            FrameTimestamp::UnixMicros(unix_micros) => {
+                context.clock_offsets.correct(client, unix_micros)
+            }
+            relative => relative.resolve_at(unix_micros_now(), context.control.display_fps),
+        }
+    }
+
+    fn resolve_at(self, now_unix_micros: u128, display_fps: f64) -> u128 {
+        match self {
+            FrameTimestamp::UnixMicros(unix_micros) => unix_micros,
+            FrameTimestamp::Now { offset_micros } => (now_unix_micros as i128)
+                .saturating_add(offset_micros)
+                .max(0) as u128,
+            // Ticks are aligned to the unix epoch, so frames pushed within the same
+            // display interval resolve to the same time
+            FrameTimestamp::NextTick => {
+                let interval_micros = ((1_000_000.0 / display_fps) as u128).max(1);
+                now_unix_micros.div_ceil(interval_micros) * interval_micros
+            }
+        }
+    }
+}
+
+impl FromStr for FrameTimestamp {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        if value == "next-tick" {
+            return Ok(FrameTimestamp::NextTick);
+        }
+        if let Some(offset) = value.strip_prefix("now") {
+            let offset_micros = match offset.chars().next() {
+                None => 0,
+                Some('+') => parse_duration_micros(&offset[1..])?,
+                Some('-') => -parse_duration_micros(&offset[1..])?,
+                Some(_) => return Err(format!("invalid time offset `{}`", offset)),
+            };
+            return Ok(FrameTimestamp::Now { offset_micros });
+        }
+        if value.bytes().all(|byte| byte.is_ascii_digit()) {
+            return value
+                .parse()
+                .map(FrameTimestamp::UnixMicros)
+                .map_err(|e| format!("invalid unix micros `{}`: {}", value, e));
+        }
+
+        let date_time = OffsetDateTime::parse(value, &Rfc3339).map_err(|e| {
+            format!(
+                "`{}` is neither unix micros, `now[+-offset]`, `next-tick` nor an RFC 3339 timestamp: {}",
+                value, e
+            )
+        })?;
+        let unix_micros = u128::try_from(date_time.unix_timestamp_nanos() / 1_000)
+            .map_err(|_| format!("`{}` is before the unix epoch", value))?;
+        Ok(FrameTimestamp::UnixMicros(unix_micros))
+    }
+}
+
+impl<'de> Deserialize<'de> for FrameTimestamp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+fn parse_duration_micros(value: &str) -> Result<i128, String> {
+    let unit_start = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(unit_start);
+    let amount = amount
+        .parse::<i128>()
+        .map_err(|_| format!("invalid duration `{}`", value))?;
+    let unit_micros = match unit {
+        "us" => 1,
+        "ms" => 1_000,
+        "s" => 1_000_000,
+        "m" => 60_000_000,
+        _ => {
+            return Err(format!(
+                "unknown duration unit in `{}`, use us, ms, s or m",
+                value
+            ))
+        }
+    };
+    amount
+        .checked_mul(unit_micros)
+        .filter(|micros| *micros <= MAX_OFFSET_MICROS)
+        .ok_or_else(|| format!("duration `{}` is too long", value))
+}
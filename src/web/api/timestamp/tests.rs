@@ -0,0 +1,68 @@
+use super::*;
+
+#[test]
+fn test_parses_time_specifications() {
+    assert_eq!("1000".parse(), Ok(FrameTimestamp::UnixMicros(1_000)));
+    assert_eq!("now".parse(), Ok(FrameTimestamp::Now { offset_micros: 0 }));
+    assert_eq!(
+        "now+250ms".parse(),
+        Ok(FrameTimestamp::Now {
+            offset_micros: 250_000
+        })
+    );
+    assert_eq!(
+        "now-2s".parse(),
+        Ok(FrameTimestamp::Now {
+            offset_micros: -2_000_000
+        })
+    );
+    assert_eq!("next-tick".parse(), Ok(FrameTimestamp::NextTick));
+    assert_eq!(
+        "2024-01-01T00:00:00.5+01:00".parse(),
+        Ok(FrameTimestamp::UnixMicros(1_704_063_600_500_000))
+    );
+
+    assert!("now+250".parse::<FrameTimestamp>().is_err());
+    assert!("now*2s".parse::<FrameTimestamp>().is_err());
+    assert!("yesterday".parse::<FrameTimestamp>().is_err());
+}
+
+#[test]
+fn test_rejects_overflowing_offsets() {
+    assert!("now+10000000000000000000000000000000m"
+        .parse::<FrameTimestamp>()
+        .is_err());
+    assert!("now-170141183460469231731687303715884105727us"
+        .parse::<FrameTimestamp>()
+        .is_err());
+    assert!("now+9223372036854775807us"
+        .parse::<FrameTimestamp>()
+        .is_ok());
+    assert!("now+9223372036854775808us"
+        .parse::<FrameTimestamp>()
+        .is_err());
+}
+
+#[test]
+fn test_resolves_relative_times() {
+    let now = 1_000_010;
+    assert_eq!(
+        FrameTimestamp::Now {
+            offset_micros: 250_000
+        }
+        .resolve_at(now, 20.0),
+        1_250_010
+    );
+    assert_eq!(
+        FrameTimestamp::Now {
+            offset_micros: -2_000_000
+        }
+        .resolve_at(now, 20.0),
+        0
+    );
+    assert_eq!(FrameTimestamp::NextTick.resolve_at(now, 20.0), 1_050_000);
+    assert_eq!(
+        FrameTimestamp::NextTick.resolve_at(1_050_000, 20.0),
+        1_050_000
+    );
+}
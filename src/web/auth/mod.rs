@@ -0,0 +1,153 @@
+use crate::web::api::error::{ErrorCode, ResponseError};
+use crate::web::state::WebServerContext;
+use anyhow::anyhow;
+use axum::extract::rejection::RawPathParamsRejection;
+use axum::extract::{MatchedPath, Query, RawPathParams, Request, State};
+use axum::http::header::{AUTHORIZATION, UPGRADE, WWW_AUTHENTICATE};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+#[cfg(test)]
+mod tests;
+
+#[derive(Clone)]
+pub struct AuthToken {
+    pub token: String,
+    pub channels: Option<BTreeSet<i8>>,
+    pub read_only: bool,
+}
+
+#[derive(Deserialize)]
+struct AccessTokenQuery {
+    access_token: Option<String>,
+}
+
+enum Access {
+    Read,
+    Write,
+    Channel(i8),
+    Display,
+}
+
+pub async fn authorize(
+    State(context): State<Arc<WebServerContext>>,
+    matched_path: Option<MatchedPath>,
+    path_params: Result<RawPathParams, RawPathParamsRejection>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let Some(tokens) = &context.config.auth_tokens else {
+        return next.run(request).await;
+    };
+    // Unknown routes are left to the fallback
+    let Some(matched_path) = matched_path else {
+        return next.run(request).await;
+    };
+    // The control panel page carries no data, it asks for a token to use with the API itself
+    if is_public_route(matched_path.as_str()) {
+        return next.run(request).await;
+    }
+
+    let Some(token) = request_token(&request) else {
+        return unauthorized("missing bearer token");
+    };
+    let Some(auth_token) = tokens
+        .iter()
+        .find(|auth_token| tokens_match(&auth_token.token, &token))
+    else {
+        return unauthorized("invalid bearer token");
+    };
+
+    let channel = match &path_params {
+        Ok(path_params) => path_params
+            .iter()
+            .find(|(name, _)| *name == "channel_index")
+            .and_then(|(_, value)| context.resolve_channel(value)),
+        Err(_) => None,
+    };
+    let access = if is_read_request(&request) {
+        Access::Read
+    } else if let Some(channel) = channel {
+        Access::Channel(channel)
+    } else if is_channel_route(matched_path.as_str()) {
+        Access::Channel(0)
+    } else if is_client_route(matched_path.as_str()) {
+        Access::Write
+    } else {
+        Access::Display
+    };
+
+    let permitted = match access {
+        Access::Read => true,
+        Access::Write => !auth_token.read_only,
+        Access::Channel(channel) => {
+            !auth_token.read_only
+                && auth_token
+                    .channels
+                    .as_ref()
+                    .is_none_or(|channels| channels.contains(&channel))
+        }
+        Access::Display => !auth_token.read_only && auth_token.channels.is_none(),
+    };
+    if !permitted {
+        return ResponseError::new(
+            ErrorCode::Forbidden,
+            anyhow!("token is not permitted to access this resource"),
+        )
+        .into_response();
+    }
+
+    next.run(request).await
+}
+
+fn request_token(request: &Request) -> Option<String> {
+    let header_token = request
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+
+    // Browsers can't set headers on WebSocket and EventSource connections
+    header_token.or_else(|| {
+        Query::<AccessTokenQuery>::try_from_uri(request.uri())
+            .ok()
+            .and_then(|Query(query)| query.access_token)
+    })
+}
+
+fn is_read_request(request: &Request) -> bool {
+    let is_upgrade = request.headers().contains_key(UPGRADE);
+    (request.method() == Method::GET || request.method() == Method::HEAD) && !is_upgrade
+}
+
+fn is_channel_route(path: &str) -> bool {
+    path.starts_with("/frame") || path.starts_with("/animation")
+}
+
+// Client routes only affect the requesting client, so any token that may submit frames can use them
+fn is_client_route(path: &str) -> bool {
+    path.starts_with("/time")
+}
+
+fn is_public_route(path: &str) -> bool {
+    path == "/" || path == "/panel"
+}
+
+fn tokens_match(expected: &str, provided: &str) -> bool {
+    expected.len() == provided.len()
+        && expected
+            .bytes()
+            .zip(provided.bytes())
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+fn unauthorized(message: &'static str) -> Response {
+    let error = ResponseError::new(ErrorCode::Unauthorized, anyhow!(message));
+    ([(WWW_AUTHENTICATE, "Bearer")], error).into_response()
+}
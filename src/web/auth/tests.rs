@@ -0,0 +1,166 @@
+use super::*;
+use crate::web::rate_limit::ClientAddr;
+use crate::web::routes::build_routes;
+use crate::web::tests::test_context;
+use axum::body::Body;
+use axum::extract::ConnectInfo;
+use axum::http::header::CONTENT_TYPE;
+use axum::http::StatusCode;
+use axum::Router;
+use tower::ServiceExt;
+
+const FRAME_BODY: &str = r#"{"frame":{"width":1,"height":1,"pixels_b64":"AAAA"}}"#;
+const FRAMES_BODY: &str =
+    r#"{"frames":[{"unix_micros":0,"frame":{"width":1,"height":1,"pixels_b64":"AAAA"}}]}"#;
+const ANIMATION_BODY: &str =
+    r#"{"frames":[{"duration_millis":100,"frame":{"width":1,"height":1,"pixels_b64":"AAAA"}}]}"#;
+
+fn auth_token(token: &str, channels: Option<&[i8]>, read_only: bool) -> AuthToken {
+    AuthToken {
+        token: token.to_string(),
+        channels: channels.map(|channels| channels.iter().copied().collect()),
+        read_only,
+    }
+}
+
+fn routes() -> Router {
+    let mut context = test_context();
+    context.config.auth_tokens = Some(vec![
+        auth_token("admin", None, false),
+        auth_token("reader", None, true),
+        auth_token("alerts", Some(&[1]), false),
+    ]);
+    build_routes(Arc::new(context))
+}
+
+async fn send(method: Method, uri: &str, token: Option<&str>, body: &'static str) -> Response {
+    let mut request = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header(CONTENT_TYPE, "application/json");
+    if let Some(token) = token {
+        request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+    }
+    let mut request = request.body(Body::from(body)).unwrap();
+    request
+        .extensions_mut()
+        .insert(ConnectInfo(ClientAddr("127.0.0.1:1234".parse().unwrap())));
+    routes().oneshot(request).await.unwrap()
+}
+
+#[tokio::test]
+async fn test_rejects_missing_and_invalid_tokens() {
+    for token in [None, Some("wrong"), Some("admin2")] {
+        let response = send(Method::GET, "/meta", token, "").await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert_eq!(response.headers()[WWW_AUTHENTICATE], "Bearer");
+    }
+
+    let response = send(Method::GET, "/meta", Some("admin"), "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_read_only_tokens_cannot_write() {
+    let response = send(Method::GET, "/queue", Some("reader"), "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+
+    for (method, uri, body) in [
+        (Method::POST, "/frame/now", FRAME_BODY),
+        (Method::PUT, "/display/brightness", r#"{"brightness":50}"#),
+        (Method::DELETE, "/queue/channel/1", ""),
+    ] {
+        let response = send(method.clone(), uri, Some("reader"), body).await;
+        assert_eq!(
+            response.status(),
+            StatusCode::FORBIDDEN,
+            "{} {}",
+            method,
+            uri
+        );
+
+        let response = send(method.clone(), uri, Some("admin"), body).await;
+        assert!(response.status().is_success(), "{} {}", method, uri);
+    }
+}
+
+#[tokio::test]
+async fn test_channel_tokens_limited_to_their_channels() {
+    for (method, uri, body) in [
+        (Method::POST, "/frame/now", FRAME_BODY),
+        (Method::POST, "/frame/now/channel/2", FRAME_BODY),
+        (Method::POST, "/frames", FRAMES_BODY),
+        (Method::POST, "/frames/channel/0", FRAMES_BODY),
+        (Method::PUT, "/animation", ANIMATION_BODY),
+        (Method::DELETE, "/animation/channel/2", ""),
+    ] {
+        let response = send(method, uri, Some("alerts"), body).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+    }
+
+    for (method, uri, body) in [
+        (Method::POST, "/frame/now/channel/1", FRAME_BODY),
+        (Method::POST, "/frame/now/channel/alerts", FRAME_BODY),
+        (Method::POST, "/frames/channel/1", FRAMES_BODY),
+        (Method::PUT, "/animation/channel/alerts", ANIMATION_BODY),
+    ] {
+        let response = send(method, uri, Some("alerts"), body).await;
+        assert!(response.status().is_success(), "{}", uri);
+    }
+}
+
+#[tokio::test]
+async fn test_channel_tokens_cannot_control_display() {
+    for (uri, body) in [
+        ("/display/brightness", r#"{"brightness":50}"#),
+        ("/display/output", r#"{"state":"blank"}"#),
+    ] {
+        let response = send(Method::PUT, uri, Some("alerts"), body).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN, "{}", uri);
+    }
+
+    let response = send(Method::GET, "/display/brightness", Some("alerts"), "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_accepts_access_token_query() {
+    let response = send(Method::GET, "/events?access_token=reader", None, "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = send(Method::GET, "/events?access_token=wrong", None, "").await;
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Upgrade requests submit frames, so the token has to be allowed to write to the channel
+    let upgrade = |uri: &str| {
+        let mut request = Request::builder()
+            .uri(uri)
+            .header(UPGRADE, "websocket")
+            .body(Body::empty())
+            .unwrap();
+        request
+            .extensions_mut()
+            .insert(ConnectInfo(ClientAddr("127.0.0.1:1234".parse().unwrap())));
+        routes().oneshot(request)
+    };
+    let response = upgrade("/frame/stream/channel/1?access_token=reader")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = upgrade("/frame/stream?access_token=alerts").await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = upgrade("/frame/stream/channel/1?access_token=alerts")
+        .await
+        .unwrap();
+    assert!(!matches!(
+        response.status(),
+        StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN
+    ));
+}
+
+#[tokio::test]
+async fn test_panel_is_public() {
+    let response = send(Method::GET, "/", None, "").await;
+    assert_eq!(response.status(), StatusCode::SEE_OTHER);
+    let response = send(Method::GET, "/panel", None, "").await;
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,104 @@
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::RwLock;
+use std::time::SystemTime;
+use tokio::net::UdpSocket;
+use tokio_util::sync::CancellationToken;
+
+#[cfg(test)]
+mod tests;
+
+// Time sync packets start with this magic, requests carry the client's transmit time
+// and responses echo it followed by the server's receive and transmit times,
+// all as little endian `u64` unix microseconds.
+const TIME_SYNC_MAGIC: &[u8; 4] = b"RGBT";
+const TIME_SYNC_REQUEST_SIZE: usize = 12;
+const TIME_SYNC_RESPONSE_SIZE: usize = 28;
+
+pub fn unix_micros_now() -> u128 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .expect("time went backwards")
+        .as_micros()
+}
+
+/// Clock offsets registered by clients, as their clock minus the server clock.
+#[derive(Default)]
+pub struct ClockOffsets {
+    offsets: RwLock<HashMap<IpAddr, i64>>,
+}
+
+impl ClockOffsets {
+    pub fn get(&self, client: IpAddr) -> Option<i64> {
+        self.offsets.read().unwrap().get(&client).copied()
+    }
+
+    pub fn register(&self, client: IpAddr, offset_micros: i64) {
+        self.offsets.write().unwrap().insert(client, offset_micros);
+    }
+
+    pub fn remove(&self, client: IpAddr) -> bool {
+        self.offsets.write().unwrap().remove(&client).is_some()
+    }
+
+    /// Converts a timestamp from the client's clock to the server's clock.
+    pub fn correct(&self, client: IpAddr, unix_micros: u128) -> u128 {
+        match self.get(client) {
+            Some(offset_micros) if offset_micros >= 0 => {
+                unix_micros.saturating_sub(offset_micros as u128)
+            }
+            Some(offset_micros) => unix_micros.saturating_add(offset_micros.unsigned_abs() as u128),
+            None => unix_micros,
+        }
+    }
+}
+
+fn time_sync_response(
+    request: &[u8],
+    received_unix_micros: u128,
+    sent_unix_micros: u128,
+) -> Option<[u8; TIME_SYNC_RESPONSE_SIZE]> {
+    if request.len() != TIME_SYNC_REQUEST_SIZE || !request.starts_with(TIME_SYNC_MAGIC) {
+        return None;
+    }
+
+    let mut response = [0; TIME_SYNC_RESPONSE_SIZE];
+    response[0..4].copy_from_slice(TIME_SYNC_MAGIC);
+    response[4..12].copy_from_slice(&request[4..12]);
+    response[12..20].copy_from_slice(&(received_unix_micros as u64).to_le_bytes());
+    response[20..28].copy_from_slice(&(sent_unix_micros as u64).to_le_bytes());
+    Some(response)
+}
+
+pub async fn run_time_sync_server(
+    socket: SocketAddr,
+    shutdown_token: CancellationToken,
+) -> io::Result<()> {
+    let udp_socket = UdpSocket::bind(socket).await?;
+    eprintln!("time sync listening on udp {}", socket);
+
+    let mut request = [0; TIME_SYNC_REQUEST_SIZE + 1];
+    loop {
+        let (size, client) = tokio::select! {
+            _ = shutdown_token.cancelled() => return Ok(()),
+            received = udp_socket.recv_from(&mut request) => match received {
+                Ok(received) => received,
+                Err(e) => {
+                    eprintln!("failed to receive time sync request: {}", e);
+                    continue;
+                }
+            },
+        };
+        let received_unix_micros = unix_micros_now();
+
+        let Some(response) =
+            time_sync_response(&request[..size], received_unix_micros, unix_micros_now())
+        else {
+            continue;
+        };
+        if let Err(e) = udp_socket.send_to(&response, client).await {
+            eprintln!("failed to answer time sync request from {}: {}", client, e);
+        }
+    }
+}
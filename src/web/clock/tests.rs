@@ -0,0 +1,42 @@
+use super::*;
+use std::net::Ipv4Addr;
+
+const CLIENT: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+
+#[test]
+fn test_registered_offsets_correct_client_timestamps() {
+    let offsets = ClockOffsets::default();
+    assert_eq!(offsets.correct(CLIENT, 10_000), 10_000);
+
+    offsets.register(CLIENT, 2_500);
+    assert_eq!(offsets.correct(CLIENT, 10_000), 7_500);
+    offsets.register(CLIENT, -2_500);
+    assert_eq!(offsets.correct(CLIENT, 10_000), 12_500);
+
+    assert!(offsets.remove(CLIENT));
+    assert_eq!(offsets.correct(CLIENT, 10_000), 10_000);
+}
+
+#[test]
+fn test_time_sync_response_echoes_client_time() {
+    let mut request = TIME_SYNC_MAGIC.to_vec();
+    request.extend_from_slice(&1_000u64.to_le_bytes());
+
+    let response = time_sync_response(&request, 2_000, 2_050).unwrap();
+    assert_eq!(&response[0..4], TIME_SYNC_MAGIC);
+    assert_eq!(
+        u64::from_le_bytes(response[4..12].try_into().unwrap()),
+        1_000
+    );
+    assert_eq!(
+        u64::from_le_bytes(response[12..20].try_into().unwrap()),
+        2_000
+    );
+    assert_eq!(
+        u64::from_le_bytes(response[20..28].try_into().unwrap()),
+        2_050
+    );
+
+    assert!(time_sync_response(&request[..8], 2_000, 2_050).is_none());
+    assert!(time_sync_response(&[0; TIME_SYNC_REQUEST_SIZE], 2_000, 2_050).is_none());
+}
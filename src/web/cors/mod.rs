@@ -0,0 +1,89 @@
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::str::FromStr;
+use std::time::Duration;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer, ExposeHeaders};
+
+#[cfg(test)]
+mod tests;
+
+pub const DEFAULT_ALLOWED_METHODS: [&str; 5] = ["GET", "HEAD", "POST", "PUT", "DELETE"];
+pub const DEFAULT_ALLOWED_HEADERS: [&str; 7] = [
+    "Authorization",
+    "Content-Type",
+    "Content-Encoding",
+    "Frame-Width",
+    "Frame-Height",
+    "Frame-Format",
+    "Frame-Palette",
+];
+// Headers of frame checks, display snapshots and rate limited responses
+pub const DEFAULT_EXPOSED_HEADERS: [&str; 6] = [
+    "Display-Width",
+    "Display-Height",
+    "Display-FPS",
+    "Frame-Width",
+    "Frame-Height",
+    "Retry-After",
+];
+
+/// Either a wildcard (`*`) or an explicit list of allowed values.
+#[derive(Clone, Debug, PartialEq)]
+pub enum CorsList<T> {
+    Any,
+    List(Vec<T>),
+}
+
+impl<T: FromStr> CorsList<T> {
+    pub fn parse(values: &[impl AsRef<str>]) -> Result<Self, String> {
+        if values.iter().any(|value| value.as_ref() == "*") {
+            if values.len() > 1 {
+                return Err("`*` cannot be combined with other values".to_string());
+            }
+            return Ok(CorsList::Any);
+        }
+
+        values
+            .iter()
+            .map(|value| {
+                value
+                    .as_ref()
+                    .parse()
+                    .map_err(|_| format!("`{}` is not a valid value", value.as_ref()))
+            })
+            .collect::<Result<_, _>>()
+            .map(CorsList::List)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct WebServerCorsConfig {
+    pub allowed_origins: CorsList<HeaderValue>,
+    pub allowed_methods: CorsList<Method>,
+    pub allowed_headers: CorsList<HeaderName>,
+    pub exposed_headers: CorsList<HeaderName>,
+    pub max_age: Option<Duration>,
+}
+
+pub fn cors_layer(config: &WebServerCorsConfig) -> CorsLayer {
+    let layer = CorsLayer::new()
+        .allow_origin(match &config.allowed_origins {
+            CorsList::Any => AllowOrigin::any(),
+            CorsList::List(origins) => AllowOrigin::list(origins.clone()),
+        })
+        .allow_methods(match &config.allowed_methods {
+            CorsList::Any => AllowMethods::any(),
+            CorsList::List(methods) => AllowMethods::list(methods.clone()),
+        })
+        .allow_headers(match &config.allowed_headers {
+            CorsList::Any => AllowHeaders::any(),
+            CorsList::List(headers) => AllowHeaders::list(headers.clone()),
+        })
+        .expose_headers(match &config.exposed_headers {
+            CorsList::Any => ExposeHeaders::any(),
+            CorsList::List(headers) => ExposeHeaders::list(headers.clone()),
+        });
+    match config.max_age {
+        Some(max_age) => layer.max_age(max_age),
+        None => layer,
+    }
+}
@@ -0,0 +1,18 @@
+use super::*;
+
+#[test]
+fn test_parses_wildcard_and_lists() {
+    assert_eq!(CorsList::<Method>::parse(&["*"]), Ok(CorsList::Any));
+    assert_eq!(
+        CorsList::<Method>::parse(&DEFAULT_ALLOWED_METHODS[..2]),
+        Ok(CorsList::List(vec![Method::GET, Method::HEAD]))
+    );
+    assert_eq!(
+        CorsList::<HeaderName>::parse(&["Display-FPS"]),
+        Ok(CorsList::List(vec![HeaderName::from_static("display-fps")]))
+    );
+
+    assert!(CorsList::<Method>::parse(&["*", "GET"]).is_err());
+    assert!(CorsList::<HeaderName>::parse(&["Display FPS"]).is_err());
+    assert!(CorsList::<HeaderValue>::parse(&["https://example.com\n"]).is_err());
+}
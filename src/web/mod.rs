@@ -1,32 +1,152 @@
+use crate::display::{Dimensions, Pixel, Position};
+use crate::event::EventBus;
 use crate::frame::Frame;
+use crate::metrics::Metrics;
+use crate::web::auth::AuthToken;
+use crate::web::clock::{run_time_sync_server, ClockOffsets};
+use crate::web::cors::WebServerCorsConfig;
+use crate::web::rate_limit::{ClientAddr, RateLimit, RateLimiter};
 use crate::web::routes::build_routes;
 use crate::web::state::WebServerContext;
+use axum::extract::connect_info::Connected;
+use axum::serve::{IncomingStream, Listener};
+use std::collections::BTreeMap;
+use std::fmt::Debug;
 use std::future::Future;
 use std::net::SocketAddr;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio_util::sync::CancellationToken;
 
 mod api;
+pub mod auth;
+pub mod clock;
+pub mod cors;
+pub mod rate_limit;
 pub mod routes;
 pub mod state;
+#[cfg(test)]
+mod tests;
+#[cfg(feature = "tls")]
+mod tls;
 
 pub struct WebServerConfig {
     pub socket: SocketAddr,
+    pub time_sync_socket: Option<SocketAddr>,
     pub shutdown_signal: Option<Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>>,
+    pub auth_tokens: Option<Vec<AuthToken>>,
+    pub tls: Option<WebServerTlsConfig>,
+    pub channel_rate_limit: RateLimit,
+    pub client_rate_limit: RateLimit,
+    pub cors: Option<WebServerCorsConfig>,
+}
+
+#[derive(Clone, Debug)]
+// Only read by builds with the `tls` feature
+#[cfg_attr(not(feature = "tls"), allow(dead_code))]
+pub struct WebServerTlsConfig {
+    pub cert_path: PathBuf,
+    pub key_path: PathBuf,
 }
 
 pub struct WebServerControl {
     pub display_width: u32,
     pub display_height: u32,
     pub display_fps: f64,
-    pub on_frame_received: Box<dyn Fn(FrameReceivedEvent) -> Result<(), String> + Send + Sync>,
+    pub display_driver: String,
+    pub channels: Vec<ChannelInfo>,
+    pub events: EventBus,
+    pub metrics: Metrics,
+    pub display: DisplayControl,
+    pub on_frame_received: Box<dyn Fn(FrameReceivedEvent) -> FrameAcceptance + Send + Sync>,
+    pub on_frames_received: Box<dyn Fn(FramesReceivedEvent) -> Vec<FrameAcceptance> + Send + Sync>,
     pub on_frame_superseded_check: Box<dyn Fn(FrameSupersededCheckEvent) -> bool + Send + Sync>,
+    pub on_queue_status_check: Box<dyn Fn(QueueStatusCheckEvent) -> QueueStatus + Send + Sync>,
+    pub on_queued_frames_check:
+        Box<dyn Fn(QueuedFramesCheckEvent) -> Vec<QueuedFrame> + Send + Sync>,
+    pub on_queued_frames_removal: Box<dyn Fn(QueuedFramesRemovalEvent) -> usize + Send + Sync>,
+    pub on_animation_received:
+        Box<dyn Fn(AnimationReceivedEvent) -> Result<(), FrameRejection> + Send + Sync>,
+    pub on_animation_cancelled: Box<dyn Fn(AnimationCancelledEvent) -> bool + Send + Sync>,
+}
+
+#[derive(Clone)]
+pub struct ChannelInfo {
+    pub name: String,
+    pub priority: i8,
+    pub idle_seconds: Option<f64>,
+    pub allowed_dimensions: Option<Vec<Dimensions>>,
+    pub description: Option<String>,
+    pub z_order: Option<i32>,
+    pub opacity: Option<f64>,
+}
+
+pub struct DisplayControl {
+    pub on_snapshot_request: Box<dyn Fn() -> Option<Vec<Pixel>> + Send + Sync>,
+    pub on_brightness_check: Box<dyn Fn() -> BrightnessStatus + Send + Sync>,
+    pub on_brightness_change: Box<dyn Fn(BrightnessChangeEvent) -> BrightnessStatus + Send + Sync>,
+    pub on_output_check: Box<dyn Fn() -> OutputState + Send + Sync>,
+    pub on_output_change: Box<dyn Fn(OutputChangeEvent) + Send + Sync>,
+}
+
+#[derive(Clone, Copy)]
+pub enum OutputState {
+    On,
+    Blank,
+    Frozen,
+}
+
+pub struct OutputChangeEvent {
+    pub state: OutputState,
+}
+
+pub struct BrightnessChangeEvent {
+    pub level: u8,
+}
+
+pub struct BrightnessStatus {
+    pub level: u8,
+    pub is_native: bool,
 }
 
 pub struct FrameReceivedEvent {
     pub channel: Option<i8>,
     pub unix_micros: u128,
     pub frame: Frame,
+    pub patch_position: Option<Position>,
+}
+
+pub struct FramesReceivedEvent {
+    pub channel: Option<i8>,
+    pub frames: Vec<ReceivedFrame>,
+}
+
+pub struct ReceivedFrame {
+    pub unix_micros: u128,
+    pub frame: Frame,
+}
+
+pub enum FrameAcceptance {
+    Accepted,
+    Superseded,
+    Rejected(FrameRejection),
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum FrameRejection {
+    #[error("frame too large")]
+    FrameTooLarge,
+    #[error("frame placement exceeds display bounds")]
+    PlacementOutOfBounds,
+    #[error("frame dimensions {width}x{height} are not allowed on this channel")]
+    DimensionsNotAllowed { width: u32, height: u32 },
+    #[error("no frame to patch on channel")]
+    MissingPatchBase,
+    #[error("{0}")]
+    InvalidAnimation(String),
 }
 
 pub struct FrameSupersededCheckEvent {
@@ -34,18 +154,126 @@ pub struct FrameSupersededCheckEvent {
     pub unix_micros: u128,
 }
 
+pub struct QueueStatusCheckEvent {
+    pub unix_micros: u128,
+}
+
+pub struct QueueStatus {
+    pub live_channel: Option<i8>,
+    pub channel_queue_depths: BTreeMap<i8, usize>,
+    pub buffer_capacity: usize,
+    pub idle_seconds: f64,
+}
+
+// Unlike frame submissions, queue management without a channel targets all channels
+pub struct QueuedFramesCheckEvent {
+    pub channel: Option<i8>,
+}
+
+pub struct QueuedFrame {
+    pub channel: i8,
+    pub unix_micros: u128,
+    pub width: u32,
+    pub height: u32,
+    pub is_patch: bool,
+}
+
+pub struct QueuedFramesRemovalEvent {
+    pub channel: Option<i8>,
+    pub from_unix_micros: Option<u128>,
+    pub until_unix_micros: Option<u128>,
+}
+
+pub struct AnimationReceivedEvent {
+    pub channel: Option<i8>,
+    pub start_unix_micros: u128,
+    pub frames: Vec<AnimationFrame>,
+    pub loop_count: Option<u32>,
+}
+
+pub struct AnimationFrame {
+    pub frame: Frame,
+    pub duration: Duration,
+}
+
+pub struct AnimationCancelledEvent {
+    pub channel: Option<i8>,
+}
+
+async fn serve<L>(
+    listener: L,
+    context: Arc<WebServerContext>,
+    shutdown_signal: Option<Pin<Box<dyn Future<Output = ()> + Send + Sync + 'static>>>,
+) -> std::io::Result<()>
+where
+    L: Listener,
+    L::Addr: Debug,
+    ClientAddr: for<'a> Connected<IncomingStream<'a, L>>,
+{
+    let routes = build_routes(Arc::clone(&context));
+    let server_future = axum::serve(
+        listener,
+        routes.into_make_service_with_connect_info::<ClientAddr>(),
+    );
+    if let Some(shutdown_signal) = shutdown_signal {
+        // Long-lived responses like event streams have to end before a graceful shutdown completes
+        let shutdown_token = context.shutdown_token.clone();
+        server_future
+            .with_graceful_shutdown(async move {
+                shutdown_signal.await;
+                shutdown_token.cancel();
+            })
+            .await
+    } else {
+        server_future.await
+    }
+}
+
 pub async fn run_server(mut config: WebServerConfig, control: WebServerControl) {
     let shutdown_signal = config.shutdown_signal.take();
     let listener = tokio::net::TcpListener::bind(config.socket).await.unwrap();
 
-    let context = Arc::new(WebServerContext { config, control });
+    let rate_limiter = RateLimiter::new(config.channel_rate_limit, config.client_rate_limit);
+    let context = Arc::new(WebServerContext {
+        rate_limiter,
+        clock_offsets: ClockOffsets::default(),
+        config,
+        control,
+        started_at: Instant::now(),
+        shutdown_token: CancellationToken::new(),
+    });
+
+    if let Some(time_sync_socket) = context.config.time_sync_socket {
+        let shutdown_token = context.shutdown_token.clone();
+        tokio::spawn(async move {
+            if let Err(e) = run_time_sync_server(time_sync_socket, shutdown_token).await {
+                eprintln!("time sync server error: {}", e);
+            }
+        });
+    }
 
-    let server_future = axum::serve(listener, build_routes(Arc::clone(&context)));
     eprintln!("listening on {}", context.config.socket);
-    let server_result = if let Some(shutdown_signal) = shutdown_signal {
-        server_future.with_graceful_shutdown(shutdown_signal).await
-    } else {
-        server_future.await
+    let server_result = match &context.config.tls {
+        #[cfg(feature = "tls")]
+        Some(tls_config) => {
+            use crate::web::tls::{ReloadingCertResolver, TlsListener};
+
+            let resolver = ReloadingCertResolver::new(tls_config.clone())
+                .unwrap_or_else(|e| panic!("failed to load tls certificate: {:#}", e));
+            let resolver = Arc::new(resolver);
+            Arc::clone(&resolver).watch(context.shutdown_token.clone());
+            serve(
+                TlsListener::new(listener, resolver),
+                Arc::clone(&context),
+                shutdown_signal,
+            )
+            .await
+        }
+        #[cfg(not(feature = "tls"))]
+        Some(_) => Err(std::io::Error::other(
+            "feature `tls` is not enabled but required for `server.tls`",
+        )),
+        None => serve(listener, Arc::clone(&context), shutdown_signal).await,
     };
     match server_result {
         Ok(()) => eprintln!("server shutdown {}", context.config.socket),
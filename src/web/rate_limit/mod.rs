@@ -0,0 +1,216 @@
+use axum::extract::connect_info::Connected;
+use axum::serve::IncomingStream;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::net::TcpListener;
+
+#[cfg(test)]
+mod tests;
+
+// Client buckets idle for this long are full again and can be forgotten
+const CLIENT_BUCKET_EXPIRY: Duration = Duration::from_secs(60);
+const CLIENT_BUCKET_PRUNE_THRESHOLD: usize = 1024;
+
+#[derive(Clone, Copy, Default, Debug)]
+pub struct RateLimit {
+    pub frames_per_second: Option<f64>,
+    pub bytes_per_second: Option<f64>,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ClientAddr(pub SocketAddr);
+
+impl Connected<IncomingStream<'_, TcpListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        ClientAddr(*stream.remote_addr())
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("rate limit exceeded, retry after {} ms", retry_after.as_millis())]
+pub struct RateLimitExceeded {
+    pub retry_after: Duration,
+}
+
+#[derive(Default)]
+pub struct RateLimitRejections {
+    pub total: u64,
+    pub channels: BTreeMap<i8, u64>,
+    pub clients: BTreeMap<IpAddr, u64>,
+}
+
+pub struct RateLimiter {
+    channel_limit: RateLimit,
+    client_limit: RateLimit,
+    state: Mutex<RateLimiterState>,
+}
+
+#[derive(Default)]
+struct RateLimiterState {
+    channel_buckets: HashMap<i8, RateLimitBuckets>,
+    client_buckets: HashMap<IpAddr, RateLimitBuckets>,
+    rejections: RateLimitRejections,
+}
+
+struct RateLimitBuckets {
+    frames: TokenBucket,
+    bytes: TokenBucket,
+}
+
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(channel_limit: RateLimit, client_limit: RateLimit) -> Self {
+        Self {
+            channel_limit,
+            client_limit,
+            state: Mutex::new(RateLimiterState::default()),
+        }
+    }
+
+    pub fn check(
+        &self,
+        channel: i8,
+        client: IpAddr,
+        frames: usize,
+        bytes: usize,
+    ) -> Result<(), RateLimitExceeded> {
+        self.check_at(Instant::now(), channel, client, frames, bytes)
+    }
+
+    fn check_at(
+        &self,
+        now: Instant,
+        channel: i8,
+        client: IpAddr,
+        frames: usize,
+        bytes: usize,
+    ) -> Result<(), RateLimitExceeded> {
+        let mut state = self.state.lock().unwrap();
+        let RateLimiterState {
+            channel_buckets,
+            client_buckets,
+            rejections,
+        } = &mut *state;
+
+        if client_buckets.len() > CLIENT_BUCKET_PRUNE_THRESHOLD {
+            client_buckets.retain(|_, buckets| {
+                now.duration_since(buckets.updated_at()) < CLIENT_BUCKET_EXPIRY
+            });
+        }
+
+        let channel_buckets = buckets_for(channel_buckets, channel, &self.channel_limit, now);
+        let client_buckets = buckets_for(client_buckets, client, &self.client_limit, now);
+        let (frames, bytes) = (frames as f64, bytes as f64);
+
+        let channel_wait = channel_buckets.wait_time(&self.channel_limit, frames, bytes);
+        let client_wait = client_buckets.wait_time(&self.client_limit, frames, bytes);
+        if channel_wait.is_some() {
+            *rejections.channels.entry(channel).or_default() += 1;
+        }
+        if client_wait.is_some() {
+            *rejections.clients.entry(client).or_default() += 1;
+        }
+        if let Some(retry_after) = channel_wait.max(client_wait) {
+            rejections.total += 1;
+            return Err(RateLimitExceeded { retry_after });
+        }
+
+        channel_buckets.consume(frames, bytes);
+        client_buckets.consume(frames, bytes);
+        Ok(())
+    }
+
+    pub fn rejections(&self) -> RateLimitRejections {
+        let state = self.state.lock().unwrap();
+        RateLimitRejections {
+            total: state.rejections.total,
+            channels: state.rejections.channels.clone(),
+            clients: state.rejections.clients.clone(),
+        }
+    }
+}
+
+fn buckets_for<'a, K: Eq + Hash>(
+    buckets: &'a mut HashMap<K, RateLimitBuckets>,
+    key: K,
+    limit: &RateLimit,
+    now: Instant,
+) -> &'a mut RateLimitBuckets {
+    let buckets = buckets
+        .entry(key)
+        .or_insert_with(|| RateLimitBuckets::new(limit, now));
+    buckets.refill(limit, now);
+    buckets
+}
+
+impl RateLimitBuckets {
+    // Buckets hold one second worth of budget, so short bursts up to the rate are allowed
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            frames: TokenBucket::new(limit.frames_per_second.unwrap_or(0.0), now),
+            bytes: TokenBucket::new(limit.bytes_per_second.unwrap_or(0.0), now),
+        }
+    }
+
+    fn updated_at(&self) -> Instant {
+        self.frames.updated_at
+    }
+
+    fn refill(&mut self, limit: &RateLimit, now: Instant) {
+        if let Some(rate) = limit.frames_per_second {
+            self.frames.refill(rate, now);
+        }
+        if let Some(rate) = limit.bytes_per_second {
+            self.bytes.refill(rate, now);
+        }
+        self.frames.updated_at = now;
+        self.bytes.updated_at = now;
+    }
+
+    fn wait_time(&self, limit: &RateLimit, frames: f64, bytes: f64) -> Option<Duration> {
+        let frames_wait = limit
+            .frames_per_second
+            .and_then(|rate| self.frames.wait_time(rate, frames));
+        let bytes_wait = limit
+            .bytes_per_second
+            .and_then(|rate| self.bytes.wait_time(rate, bytes));
+        frames_wait.max(bytes_wait)
+    }
+
+    fn consume(&mut self, frames: f64, bytes: f64) {
+        self.frames.tokens -= frames;
+        self.bytes.tokens -= bytes;
+    }
+}
+
+impl TokenBucket {
+    fn new(rate: f64, now: Instant) -> Self {
+        Self {
+            tokens: rate,
+            updated_at: now,
+        }
+    }
+
+    fn refill(&mut self, rate: f64, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = f64::min(rate, self.tokens + elapsed * rate);
+    }
+
+    // Submissions larger than the whole bucket only need a full bucket and leave it in debt,
+    // otherwise they could never be accepted
+    fn wait_time(&self, rate: f64, cost: f64) -> Option<Duration> {
+        let required = f64::min(cost, rate);
+        if self.tokens >= required {
+            return None;
+        }
+        Some(Duration::from_secs_f64((required - self.tokens) / rate))
+    }
+}
@@ -0,0 +1,54 @@
+use super::*;
+use std::net::Ipv4Addr;
+
+const CLIENT_A: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+const CLIENT_B: IpAddr = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
+
+fn frame_limit(frames_per_second: f64) -> RateLimit {
+    RateLimit {
+        frames_per_second: Some(frames_per_second),
+        bytes_per_second: None,
+    }
+}
+
+#[test]
+fn test_channel_limit_rejects_until_refilled() {
+    let limiter = RateLimiter::new(frame_limit(2.0), RateLimit::default());
+    let now = Instant::now();
+
+    assert!(limiter.check_at(now, 0, CLIENT_A, 1, 0).is_ok());
+    assert!(limiter.check_at(now, 0, CLIENT_B, 1, 0).is_ok());
+    let exceeded = limiter.check_at(now, 0, CLIENT_A, 1, 0).unwrap_err();
+    assert_eq!(exceeded.retry_after, Duration::from_millis(500));
+    assert!(limiter.check_at(now, 1, CLIENT_A, 1, 0).is_ok());
+    assert!(limiter
+        .check_at(now + Duration::from_millis(500), 0, CLIENT_A, 1, 0)
+        .is_ok());
+
+    let rejections = limiter.rejections();
+    assert_eq!(rejections.total, 1);
+    assert_eq!(rejections.channels.get(&0), Some(&1));
+    assert!(rejections.clients.is_empty());
+}
+
+#[test]
+fn test_client_limit_counts_bytes_across_channels() {
+    let limiter = RateLimiter::new(
+        RateLimit::default(),
+        RateLimit {
+            frames_per_second: None,
+            bytes_per_second: Some(1_000.0),
+        },
+    );
+    let now = Instant::now();
+
+    // A submission larger than the bucket is accepted once and leaves the bucket in debt
+    assert!(limiter.check_at(now, 0, CLIENT_A, 1, 3_000).is_ok());
+    let exceeded = limiter.check_at(now, 1, CLIENT_A, 1, 100).unwrap_err();
+    assert_eq!(exceeded.retry_after, Duration::from_millis(2_100));
+    assert!(limiter.check_at(now, 1, CLIENT_B, 1, 100).is_ok());
+
+    let rejections = limiter.rejections();
+    assert_eq!(rejections.clients.get(&CLIENT_A), Some(&1));
+    assert!(rejections.channels.is_empty());
+}
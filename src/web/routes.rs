@@ -1,12 +1,36 @@
-use crate::web::api::{frames_router, meta_router};
+use crate::web::api::error::json_error_response;
+use crate::web::api::{
+    animations_router, display_router, events_router, frames_router, meta_router, metrics_router,
+    panel_router, queue_router, time_router,
+};
+use crate::web::auth::authorize;
+use crate::web::cors::cors_layer;
 use crate::web::state::WebServerContext;
-use axum::Router;
+use axum::{middleware, Router};
 use std::sync::Arc;
 use tower_http::decompression::RequestDecompressionLayer;
 
 pub fn build_routes(context: Arc<WebServerContext>) -> Router {
-    Router::new()
+    let routes = Router::new()
         .merge(frames_router(&context))
+        .merge(animations_router(&context))
+        .merge(display_router(&context))
+        .merge(events_router(&context))
+        .merge(meta_router(&context))
+        .merge(metrics_router(&context))
+        .merge(panel_router(&context))
+        .merge(queue_router(&context))
+        .merge(time_router(&context))
+        .layer(middleware::from_fn_with_state(
+            Arc::clone(&context),
+            authorize,
+        ))
         .layer(RequestDecompressionLayer::new())
-        .with_state(context)
+        .layer(middleware::map_response(json_error_response));
+    // Outermost, so preflight requests are answered before authorization
+    let routes = match &context.config.cors {
+        Some(cors) => routes.layer(cors_layer(cors)),
+        None => routes,
+    };
+    routes.with_state(context)
 }
@@ -1,6 +1,27 @@
+use crate::web::clock::ClockOffsets;
+use crate::web::rate_limit::RateLimiter;
 use crate::web::{WebServerConfig, WebServerControl};
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
 
 pub struct WebServerContext {
     pub config: WebServerConfig,
     pub control: WebServerControl,
+    pub started_at: Instant,
+    pub shutdown_token: CancellationToken,
+    pub rate_limiter: RateLimiter,
+    pub clock_offsets: ClockOffsets,
+}
+
+impl WebServerContext {
+    pub fn resolve_channel(&self, channel: &str) -> Option<i8> {
+        if let Ok(index) = channel.parse::<i8>() {
+            return Some(index);
+        }
+        self.control
+            .channels
+            .iter()
+            .find(|channel_info| channel_info.name == channel)
+            .map(|channel_info| channel_info.priority)
+    }
 }
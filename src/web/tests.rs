@@ -0,0 +1,83 @@
+use crate::event::EventBus;
+use crate::metrics::Metrics;
+use crate::web::clock::ClockOffsets;
+use crate::web::rate_limit::{RateLimit, RateLimiter};
+use crate::web::state::WebServerContext;
+use crate::web::{
+    BrightnessStatus, ChannelInfo, DisplayControl, FrameAcceptance, OutputState, QueueStatus,
+    WebServerConfig, WebServerControl,
+};
+use std::collections::BTreeMap;
+use std::time::Instant;
+use tokio_util::sync::CancellationToken;
+
+/// Context of an 8x4 display with an `alerts` channel at priority 1, accepting every frame.
+pub fn test_context() -> WebServerContext {
+    let config = WebServerConfig {
+        socket: "127.0.0.1:0".parse().unwrap(),
+        time_sync_socket: None,
+        shutdown_signal: None,
+        auth_tokens: None,
+        tls: None,
+        channel_rate_limit: RateLimit::default(),
+        client_rate_limit: RateLimit::default(),
+        cors: None,
+    };
+    let control = WebServerControl {
+        display_width: 8,
+        display_height: 4,
+        display_fps: 20.0,
+        display_driver: "fake".to_string(),
+        channels: vec![ChannelInfo {
+            name: "alerts".to_string(),
+            priority: 1,
+            idle_seconds: None,
+            allowed_dimensions: None,
+            description: None,
+            z_order: None,
+            opacity: None,
+        }],
+        events: EventBus::default(),
+        metrics: Metrics::default(),
+        display: DisplayControl {
+            on_snapshot_request: Box::new(|| None),
+            on_brightness_check: Box::new(|| BrightnessStatus {
+                level: 100,
+                is_native: false,
+            }),
+            on_brightness_change: Box::new(|event| BrightnessStatus {
+                level: event.level,
+                is_native: false,
+            }),
+            on_output_check: Box::new(|| OutputState::On),
+            on_output_change: Box::new(|_| {}),
+        },
+        on_frame_received: Box::new(|_| FrameAcceptance::Accepted),
+        on_frames_received: Box::new(|event| {
+            event
+                .frames
+                .iter()
+                .map(|_| FrameAcceptance::Accepted)
+                .collect()
+        }),
+        on_frame_superseded_check: Box::new(|_| false),
+        on_queue_status_check: Box::new(|_| QueueStatus {
+            live_channel: None,
+            channel_queue_depths: BTreeMap::new(),
+            buffer_capacity: 0,
+            idle_seconds: 1.0,
+        }),
+        on_queued_frames_check: Box::new(|_| Vec::new()),
+        on_queued_frames_removal: Box::new(|_| 0),
+        on_animation_received: Box::new(|_| Ok(())),
+        on_animation_cancelled: Box::new(|_| false),
+    };
+    WebServerContext {
+        config,
+        control,
+        started_at: Instant::now(),
+        shutdown_token: CancellationToken::new(),
+        rate_limiter: RateLimiter::new(RateLimit::default(), RateLimit::default()),
+        clock_offsets: ClockOffsets::default(),
+    }
+}
@@ -0,0 +1,166 @@
+use crate::web::rate_limit::ClientAddr;
+use crate::web::WebServerTlsConfig;
+use axum::extract::connect_info::Connected;
+use axum::serve::{IncomingStream, Listener};
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinSet;
+use tokio::time::{sleep, timeout};
+use tokio_rustls::rustls::crypto::ring::sign::any_supported_type;
+use tokio_rustls::rustls::pki_types::pem::PemObject;
+use tokio_rustls::rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_util::sync::CancellationToken;
+
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+const RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
+pub struct TlsListener {
+    tcp_listener: TcpListener,
+    acceptor: TlsAcceptor,
+    handshakes: JoinSet<Option<(TlsStream<TcpStream>, SocketAddr)>>,
+}
+
+impl TlsListener {
+    pub fn new(tcp_listener: TcpListener, resolver: Arc<ReloadingCertResolver>) -> Self {
+        let mut server_config = ServerConfig::builder()
+            .with_no_client_auth()
+            .with_cert_resolver(resolver);
+        server_config.alpn_protocols = vec![b"http/1.1".to_vec()];
+
+        Self {
+            tcp_listener,
+            acceptor: TlsAcceptor::from(Arc::new(server_config)),
+            handshakes: JoinSet::new(),
+        }
+    }
+}
+
+impl Listener for TlsListener {
+    type Io = TlsStream<TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        loop {
+            // Handshakes run concurrently, so a slow client can't hold up other connections
+            tokio::select! {
+                accepted = self.tcp_listener.accept() => match accepted {
+                    Ok((stream, address)) => {
+                        let acceptor = self.acceptor.clone();
+                        self.handshakes.spawn(async move {
+                            match timeout(HANDSHAKE_TIMEOUT, acceptor.accept(stream)).await {
+                                Ok(Ok(stream)) => Some((stream, address)),
+                                Ok(Err(e)) => {
+                                    eprintln!("tls handshake with {} failed: {}", address, e);
+                                    None
+                                }
+                                Err(_) => {
+                                    eprintln!("tls handshake with {} timed out", address);
+                                    None
+                                }
+                            }
+                        });
+                    }
+                    Err(e) => {
+                        eprintln!("failed to accept connection: {}", e);
+                        if !is_connection_error(&e) {
+                            sleep(Duration::from_secs(1)).await;
+                        }
+                    }
+                },
+                Some(handshake) = self.handshakes.join_next() => match handshake {
+                    Ok(Some(connection)) => return connection,
+                    Ok(None) => continue,
+                    Err(e) => eprintln!("tls handshake task failed: {}", e),
+                },
+            }
+        }
+    }
+
+    fn local_addr(&self) -> io::Result<Self::Addr> {
+        self.tcp_listener.local_addr()
+    }
+}
+
+impl Connected<IncomingStream<'_, TlsListener>> for ClientAddr {
+    fn connect_info(stream: IncomingStream<'_, TlsListener>) -> Self {
+        ClientAddr(*stream.remote_addr())
+    }
+}
+
+fn is_connection_error(error: &io::Error) -> bool {
+    matches!(
+        error.kind(),
+        io::ErrorKind::ConnectionRefused
+            | io::ErrorKind::ConnectionAborted
+            | io::ErrorKind::ConnectionReset
+    )
+}
+
+#[derive(Debug)]
+pub struct ReloadingCertResolver {
+    config: WebServerTlsConfig,
+    certified_key: RwLock<Arc<CertifiedKey>>,
+}
+
+impl ReloadingCertResolver {
+    pub fn new(config: WebServerTlsConfig) -> anyhow::Result<Self> {
+        let certified_key = load_certified_key(&config)?;
+        Ok(Self {
+            config,
+            certified_key: RwLock::new(certified_key),
+        })
+    }
+
+    pub fn watch(self: Arc<Self>, shutdown_token: CancellationToken) {
+        tokio::spawn(async move {
+            let mut last_modified = self.files_modified();
+            loop {
+                tokio::select! {
+                    _ = shutdown_token.cancelled() => break,
+                    _ = sleep(RELOAD_INTERVAL) => {}
+                }
+
+                let modified = self.files_modified();
+                if modified == last_modified {
+                    continue;
+                }
+                last_modified = modified;
+
+                match load_certified_key(&self.config) {
+                    Ok(certified_key) => {
+                        *self.certified_key.write().unwrap() = certified_key;
+                        eprintln!("reloaded tls certificate");
+                    }
+                    Err(e) => eprintln!("failed to reload tls certificate: {:#}", e),
+                }
+            }
+        });
+    }
+
+    fn files_modified(&self) -> Option<(SystemTime, SystemTime)> {
+        let cert_modified = self.config.cert_path.metadata().ok()?.modified().ok()?;
+        let key_modified = self.config.key_path.metadata().ok()?.modified().ok()?;
+        Some((cert_modified, key_modified))
+    }
+}
+
+impl ResolvesServerCert for ReloadingCertResolver {
+    fn resolve(&self, _client_hello: ClientHello) -> Option<Arc<CertifiedKey>> {
+        Some(Arc::clone(&self.certified_key.read().unwrap()))
+    }
+}
+
+fn load_certified_key(config: &WebServerTlsConfig) -> anyhow::Result<Arc<CertifiedKey>> {
+    let certs = CertificateDer::pem_file_iter(&config.cert_path)?.collect::<Result<Vec<_>, _>>()?;
+    let key = PrivateKeyDer::from_pem_file(&config.key_path)?;
+    let signing_key = any_supported_type(&key)?;
+    Ok(Arc::new(CertifiedKey::new(certs, signing_key)))
+}